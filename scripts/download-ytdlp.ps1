@@ -1,5 +1,6 @@
 # PowerShell script to download YT-DLP binaries for all platforms
-# This script downloads the latest YT-DLP releases for Windows, macOS (Intel and ARM), and Linux (x64 and ARM64)
+# This script downloads the latest YT-DLP releases for Windows, macOS (Intel and ARM), and Linux (x64 and ARM64),
+# and FFmpeg/ffprobe for Windows
 
 $resourcesDir = "src-tauri\resources"
 New-Item -ItemType Directory -Force -Path $resourcesDir | Out-Null
@@ -81,6 +82,40 @@ if ($linuxArmAssets) {
     Write-Host "Warning: Linux ARM64 binary not found (may not be available)"
 }
 
+# Download FFmpeg and ffprobe for Windows, needed for merging formats, audio extraction and embedding.
+# Static GPL build from https://github.com/BtbN/FFmpeg-Builds
+$ffmpegDir = "$resourcesDir\ffmpeg"
+$ffmpegArchive = "ffmpeg-master-latest-win64-gpl.zip"
+$tempDir = Join-Path ([System.IO.Path]::GetTempPath()) ([System.Guid]::NewGuid())
+New-Item -ItemType Directory -Force -Path $tempDir | Out-Null
+
+Write-Host "Downloading FFmpeg for Windows..."
+$ffmpegReleaseUrl = "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest"
+Invoke-WebRequest -Uri "$ffmpegReleaseUrl/$ffmpegArchive" -OutFile "$tempDir\$ffmpegArchive"
+Invoke-WebRequest -Uri "$ffmpegReleaseUrl/checksums.sha256" -OutFile "$tempDir\checksums.sha256"
+
+# Never bundle an archive that doesn't match the published checksum
+$expectedHash = Get-Content "$tempDir\checksums.sha256" |
+    ForEach-Object { $fields = $_ -split '\s+', 2; if ($fields[1] -and $fields[1].TrimStart('*') -eq $ffmpegArchive) { $fields[0] } } |
+    Select-Object -First 1
+$actualHash = (Get-FileHash -Algorithm SHA256 "$tempDir\$ffmpegArchive").Hash
+if (-not $expectedHash -or $actualHash -ne $expectedHash) {
+    Remove-Item -Recurse -Force $tempDir
+    Write-Host "Error: Checksum mismatch for ${ffmpegArchive}: expected $expectedHash, got $actualHash"
+    exit 1
+}
+Write-Host "Verified SHA-256 of $ffmpegArchive"
+
+Expand-Archive -Path "$tempDir\$ffmpegArchive" -DestinationPath $tempDir -Force
+
+if (Test-Path $ffmpegDir) {
+    Remove-Item -Recurse -Force $ffmpegDir
+}
+New-Item -ItemType Directory -Force -Path $ffmpegDir | Out-Null
+Get-ChildItem -Path $tempDir -Recurse -Include "ffmpeg.exe", "ffprobe.exe" | Copy-Item -Destination $ffmpegDir
+Remove-Item -Recurse -Force $tempDir
+Write-Host "FFmpeg downloaded to $ffmpegDir"
+
 Write-Host "`nAll binaries downloaded to $resourcesDir"
 Write-Host "Note: Make sure to make Linux/macOS binaries executable if building on those platforms"
 
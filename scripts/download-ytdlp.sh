@@ -1,6 +1,7 @@
 #!/bin/bash
 # Bash script to download YT-DLP binaries for all platforms
-# This script downloads the latest YT-DLP releases for Windows, macOS (Intel and ARM), and Linux (x64 and ARM64)
+# This script downloads the latest YT-DLP releases for Windows, macOS (Intel and ARM), and Linux (x64 and ARM64),
+# and FFmpeg/ffprobe for the platform being built
 
 # Check if jq is installed
 if ! command -v jq &> /dev/null; then
@@ -86,6 +87,109 @@ find_and_download "macos" "yt-dlp_macos" "yt-dlp_macos"
 find_and_download "linux" "yt-dlp_linux" "yt-dlp_linux"
 find_and_download "linux.*arm64" "linux.*aarch64" "yt-dlp_linux_arm64"
 
+# Download FFmpeg and ffprobe, needed for merging formats, audio extraction and embedding.
+# Only the platform being built is downloaded, as the binaries are large. Set FFMPEG_PLATFORM
+# (windows, macos_arm64, macos_x64, linux, linux_arm64) to download for another platform.
+FFMPEG_DIR="$RESOURCES_DIR/ffmpeg"
+
+if [ -z "$FFMPEG_PLATFORM" ]; then
+    case "$(uname -s)-$(uname -m)" in
+        Darwin-arm64) FFMPEG_PLATFORM="macos_arm64" ;;
+        Darwin-*) FFMPEG_PLATFORM="macos_x64" ;;
+        Linux-aarch64|Linux-arm64) FFMPEG_PLATFORM="linux_arm64" ;;
+        Linux-*) FFMPEG_PLATFORM="linux" ;;
+        MINGW*|MSYS*|CYGWIN*) FFMPEG_PLATFORM="windows" ;;
+    esac
+fi
+
+# Check a file against the SHA-256 its publisher lists for it
+verify_sha256() {
+    local file="$1"
+    local expected
+    expected=$(echo "$2" | tr '[:upper:]' '[:lower:]')
+
+    local actual
+    if command -v sha256sum &> /dev/null; then
+        actual=$(sha256sum "$file" | cut -d' ' -f1)
+    else
+        actual=$(shasum -a 256 "$file" | cut -d' ' -f1)
+    fi
+
+    if [ -z "$expected" ] || [ "$actual" != "$expected" ]; then
+        echo "Error: Checksum mismatch for $(basename "$file"): expected ${expected:-nothing}, got $actual"
+        return 1
+    fi
+    echo "Verified SHA-256 of $(basename "$file")"
+}
+
+download_ffmpeg() {
+    case "$FFMPEG_PLATFORM" in
+        windows|linux|linux_arm64|macos_arm64|macos_x64) ;;
+        *)
+            echo "Warning: Unknown FFmpeg platform '$FFMPEG_PLATFORM', skipping FFmpeg"
+            return 0
+            ;;
+    esac
+
+    local tmp_dir
+    tmp_dir=$(mktemp -d)
+
+    rm -rf "$FFMPEG_DIR"
+    mkdir -p "$FFMPEG_DIR"
+
+    case "$FFMPEG_PLATFORM" in
+        windows|linux|linux_arm64)
+            # Static GPL builds from https://github.com/BtbN/FFmpeg-Builds
+            local archive
+            case "$FFMPEG_PLATFORM" in
+                windows) archive="ffmpeg-master-latest-win64-gpl.zip" ;;
+                linux) archive="ffmpeg-master-latest-linux64-gpl.tar.xz" ;;
+                linux_arm64) archive="ffmpeg-master-latest-linuxarm64-gpl.tar.xz" ;;
+            esac
+
+            echo "Downloading FFmpeg for $FFMPEG_PLATFORM..."
+            local release_url="https://github.com/BtbN/FFmpeg-Builds/releases/download/latest"
+            curl -L -f -o "$tmp_dir/$archive" "$release_url/$archive" || return 1
+            curl -L -f -o "$tmp_dir/checksums.sha256" "$release_url/checksums.sha256" || return 1
+            verify_sha256 "$tmp_dir/$archive" \
+                "$(awk -v name="$archive" '$2 == name || $2 == "*" name { print $1 }' "$tmp_dir/checksums.sha256")" || return 1
+
+            if [[ "$archive" == *.zip ]]; then
+                unzip -q "$tmp_dir/$archive" -d "$tmp_dir" || return 1
+            else
+                tar -xJf "$tmp_dir/$archive" -C "$tmp_dir" || return 1
+            fi
+
+            cp "$tmp_dir"/ffmpeg-*/bin/ffmpeg* "$tmp_dir"/ffmpeg-*/bin/ffprobe* "$FFMPEG_DIR/" || return 1
+            ;;
+        macos_arm64|macos_x64)
+            # Static builds from https://ffmpeg.martin-riedl.de
+            local arch="arm64"
+            if [ "$FFMPEG_PLATFORM" = "macos_x64" ]; then
+                arch="amd64"
+            fi
+
+            for tool in ffmpeg ffprobe; do
+                echo "Downloading $tool for $FFMPEG_PLATFORM..."
+                local download_url="https://ffmpeg.martin-riedl.de/redirect/latest/macos/$arch/release/$tool.zip"
+                curl -L -f -o "$tmp_dir/$tool.zip" "$download_url" || return 1
+                curl -L -f -o "$tmp_dir/$tool.zip.sha256" "$download_url.sha256" || return 1
+                verify_sha256 "$tmp_dir/$tool.zip" "$(awk '{ print $1; exit }' "$tmp_dir/$tool.zip.sha256")" || return 1
+                unzip -q -o "$tmp_dir/$tool.zip" -d "$FFMPEG_DIR" || return 1
+            done
+            ;;
+    esac
+
+    chmod +x "$FFMPEG_DIR"/*
+    rm -rf "$tmp_dir"
+    echo "FFmpeg downloaded to $FFMPEG_DIR"
+}
+
+if ! download_ffmpeg; then
+    echo "Error: Failed to download FFmpeg"
+    exit 1
+fi
+
 echo ""
 echo "All binaries downloaded to $RESOURCES_DIR"
 
@@ -0,0 +1,270 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A partial or temporary YT-DLP file found in the download directory
+#[derive(Debug, Clone, Serialize)]
+pub struct LeftoverFile {
+    pub path: String,
+    pub size: u64,
+    // Unix timestamp in seconds
+    pub modified_at: u64,
+}
+
+static PARTIAL_FILE_NAME: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(
+        r"(\.part(-Frag\d+)?|\.ytdl|\.(temp|f(\d+(-\d+)?|(hls|dash|http)-[\w.-]+))\.(mp4|m4a|webm|mkv|mp3|opus|ogg|flv|aac|ts))$",
+    )
+    .unwrap()
+});
+
+/// Whether a file name looks like something YT-DLP leaves behind mid-download:
+/// `.part` files and their fragments, `.ytdl` resume state, `.temp` merge output,
+/// and unmerged `.fNNN` format streams
+pub fn is_partial_file_name(name: &str) -> bool {
+    PARTIAL_FILE_NAME.is_match(name)
+}
+
+/// Whether a file name is a partial file of the tracked file: its `.part`/`.ytdl` companions, or
+/// the `.temp` file postprocessors such as the merger write "X.mp4" through ("X.temp.mp4")
+fn is_partial_of(name: &str, tracked_name: &str) -> bool {
+    let temp_name = Path::new(tracked_name)
+        .file_stem()
+        .zip(Path::new(tracked_name).extension())
+        .map(|(stem, ext)| format!("{}.temp.{}", stem.to_string_lossy(), ext.to_string_lossy()));
+
+    (name.starts_with(tracked_name) || temp_name.as_deref() == Some(name)) && is_partial_file_name(name)
+}
+
+/// Delete the partial files belonging to the tracked download files. A tracked file itself is
+/// only removed when it is an unmerged format stream, so finished files are never touched.
+pub fn delete_partial_files(tracked_files: &[String]) {
+    for tracked in tracked_files {
+        let tracked = Path::new(tracked);
+        let (Some(dir), Some(tracked_name)) = (tracked.parent(), tracked.file_name()) else {
+            continue;
+        };
+        let tracked_name = tracked_name.to_string_lossy();
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_partial_of(&name, &tracked_name) {
+                #[cfg(debug_assertions)]
+                eprintln!("[DEBUG] Deleting partial file {:?}", entry.path());
+
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+// How many levels of subdirectories a scan descends into, e.g. `uploader/date/` from the
+// output template presets
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// Partial files in a directory and its subdirectories older than `min_age`, skipping those of
+/// downloads still in progress
+pub fn scan_leftovers(dir: &Path, min_age: Duration, active_files: &[String]) -> Result<Vec<LeftoverFile>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read download directory: {}", e))?;
+
+    let now = SystemTime::now();
+    let mut leftovers = Vec::new();
+    let mut pending = vec![(entries, 0)];
+
+    while let Some((entries, depth)) = pending.pop() {
+        for entry in entries.flatten() {
+            // Symlinks are never followed, so a scan can't leave the download directory
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                if depth < MAX_SCAN_DEPTH {
+                    if let Ok(sub_entries) = fs::read_dir(entry.path()) {
+                        pending.push((sub_entries, depth + 1));
+                    }
+                }
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !file_type.is_file() || !is_partial_file_name(&name) {
+                continue;
+            }
+
+            let path = entry.path();
+            let in_use = active_files.iter().any(|active| {
+                let active = Path::new(active);
+                active.parent() == path.parent()
+                    && active
+                        .file_name()
+                        .is_some_and(|active_name| is_partial_of(&name, &active_name.to_string_lossy()))
+            });
+            if in_use {
+                continue;
+            }
+
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+
+            let modified = metadata.modified().unwrap_or(now);
+            if now.duration_since(modified).unwrap_or_default() < min_age {
+                continue;
+            }
+
+            leftovers.push(LeftoverFile {
+                path: path.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified_at: modified
+                    .duration_since(UNIX_EPOCH)
+                    .map(|elapsed| elapsed.as_secs())
+                    .unwrap_or(0),
+            });
+        }
+    }
+
+    leftovers.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(leftovers)
+}
+
+/// Whether a path is one a scan of `dir` could have found: a partial file inside `dir`, at most
+/// `MAX_SCAN_DEPTH` subdirectories deep
+fn is_scanned_path(dir: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(dir) else {
+        return false;
+    };
+
+    let components: Vec<_> = relative.components().collect();
+    let within_dir = !components.is_empty()
+        && components.len() <= MAX_SCAN_DEPTH + 1
+        && components
+            .iter()
+            .all(|component| matches!(component, Component::Normal(_)));
+
+    within_dir
+        && path
+            .file_name()
+            .is_some_and(|name| is_partial_file_name(&name.to_string_lossy()))
+}
+
+/// Delete leftover files found by a scan. Only partial files inside `dir` are removed.
+pub fn delete_leftovers(dir: &Path, paths: &[String]) -> Result<(), String> {
+    for path in paths {
+        let path = PathBuf::from(path);
+
+        // A scan never follows symlinks, whether the file itself or one of its folders below `dir`
+        let through_symlink = path
+            .ancestors()
+            .take_while(|ancestor| *ancestor != dir)
+            .any(|ancestor| ancestor.is_symlink());
+
+        if !is_scanned_path(dir, &path) || through_symlink {
+            return Err(format!("Not a leftover download file: {}", path.display()));
+        }
+
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_partial_file_names() {
+        for name in [
+            "Title [abc].mp4.part",
+            "Title [abc].mp4.part-Frag12",
+            "Title [abc].mp4.ytdl",
+            "Title [abc].temp.mp4",
+            "Title [abc].f137.mp4",
+            "Title [abc].f251-1.webm",
+            "Title [abc].fhls-1080p.mp4",
+            "Title [abc].fdash-video_1.m4a",
+        ] {
+            assert!(is_partial_file_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn leaves_finished_files_alone() {
+        for name in [
+            "Title [abc].mp4",
+            "Title [abc].mp3",
+            "Title [abc].info.json",
+            "Title [abc].en.srt",
+            "party.mp4",
+            "Title [abc].f137.mp4.jpg",
+            "notes.temp.txt",
+        ] {
+            assert!(!is_partial_file_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn accepts_partial_files_inside_download_dir() {
+        let dir = Path::new("/Downloads");
+        assert!(is_scanned_path(dir, Path::new("/Downloads/a.mp4.part")));
+        assert!(is_scanned_path(dir, Path::new("/Downloads/Uploader/2024-01-01 - a.f137.mp4")));
+        assert!(is_scanned_path(dir, Path::new("/Downloads/a/b/c/d/e.mp4.part")));
+    }
+
+    #[test]
+    fn rejects_paths_a_scan_would_not_find() {
+        let dir = Path::new("/Downloads");
+        // Finished file, outside the directory, escaping it, too deep, or the directory itself
+        assert!(!is_scanned_path(dir, Path::new("/Downloads/a.mp4")));
+        assert!(!is_scanned_path(dir, Path::new("/Other/a.mp4.part")));
+        assert!(!is_scanned_path(dir, Path::new("/Downloads/../etc/a.mp4.part")));
+        assert!(!is_scanned_path(dir, Path::new("/Downloads/a/../../a.mp4.part")));
+        assert!(!is_scanned_path(dir, Path::new("/Downloads/a/b/c/d/e/f.mp4.part")));
+        assert!(!is_scanned_path(dir, Path::new("/Downloads")));
+    }
+
+    #[test]
+    fn matches_partial_files_of_tracked_file() {
+        assert!(is_partial_of("Title [abc].mp4.part", "Title [abc].mp4"));
+        assert!(is_partial_of("Title [abc].f137.mp4.part", "Title [abc].f137.mp4"));
+        assert!(is_partial_of("Title [abc].temp.mp4", "Title [abc].mp4"));
+
+        assert!(!is_partial_of("Title [abc].mp4", "Title [abc].mp4"));
+        assert!(!is_partial_of("Title [xyz].temp.mp4", "Title [abc].mp4"));
+        assert!(!is_partial_of("Title [abc].temp.mkv", "Title [abc].mp4"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn never_deletes_through_symlinked_folders() {
+        let root = std::env::temp_dir().join(format!("mac-ytdlp-cleanup-{}", std::process::id()));
+        let dir = root.join("Downloads");
+        let outside = root.join("Outside");
+        fs::create_dir_all(&dir).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("a.mp4.part"), b"").unwrap();
+        fs::write(dir.join("b.mp4.part"), b"").unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("linked")).unwrap();
+
+        let linked = dir.join("linked/a.mp4.part").to_string_lossy().to_string();
+        assert!(delete_leftovers(&dir, &[linked]).is_err());
+        assert!(outside.join("a.mp4.part").exists());
+
+        let direct = dir.join("b.mp4.part").to_string_lossy().to_string();
+        assert_eq!(delete_leftovers(&dir, &[direct]), Ok(()));
+        assert!(!dir.join("b.mp4.part").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,18 +1,73 @@
 use crate::ytdlp_manager;
-use crate::updater;
-use crate::config;
+use crate::ffmpeg_manager;
+use crate::updater::{self, ReleaseSummary};
+use crate::cleanup::{self, LeftoverFile};
+use crate::config::{self, CleanupPolicy, RetryPolicy, UpdateSettings};
+use crate::error::{AppError, ErrorKind};
+use crate::download_manager::{self, DownloadJob, JobId};
+use crate::download_options::DownloadOptions;
+use crate::history::{self, HistoryEntry, HistoryFilter};
+use crate::output_template::{self, OutputTemplatePreset, OutputTemplatePreview};
+use crate::playlist::{self, PlaylistPage, ProbeId};
+use crate::probe;
+use crate::release_cache;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::sync::Mutex;
-use tauri::Emitter;
-use tokio::sync::oneshot;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VideoInfo {
+    pub id: Option<String>,
+    pub extractor: Option<String>,
+    pub webpage_url: Option<String>,
     pub title: String,
+    pub description: Option<String>,
+    // YYYYMMDD
+    pub upload_date: Option<String>,
     pub duration: Option<u64>,
     pub uploader: Option<String>,
+    pub channel: Option<String>,
+    pub channel_url: Option<String>,
     pub view_count: Option<u64>,
+    pub like_count: Option<u64>,
+    pub tags: Vec<String>,
+    pub categories: Vec<String>,
+    // Minimum viewer age, 0 when unrestricted
+    pub age_limit: Option<u64>,
+    // "not_live", "is_live", "is_upcoming", "was_live" or "post_live"
+    pub live_status: Option<String>,
+    // "public", "unlisted", "private", "needs_auth", "premium_only" or "subscriber_only"
+    pub availability: Option<String>,
+    // Ordered from worst to best, as YT-DLP lists them
+    pub thumbnails: Vec<Thumbnail>,
+    pub chapters: Vec<Chapter>,
+    // Manual subtitles first, then auto-generated captions
+    pub subtitles: Vec<SubtitleTrack>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Thumbnail {
+    pub url: String,
+    pub id: Option<String>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Chapter {
+    pub title: Option<String>,
+    // Seconds from the start of the video
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubtitleTrack {
+    pub language: String,
+    pub name: Option<String>,
+    // True for auto-generated captions
+    pub automatic: bool,
+    // Available file formats, e.g. "vtt", "srv3", "json3"
+    pub formats: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,6 +77,33 @@ pub struct VideoFormat {
     pub ext: String,
     pub filesize: Option<u64>,
     pub quality_label: String,
+    pub vcodec: String,
+    // "none" for video-only streams
+    pub acodec: String,
+    pub fps: Option<f64>,
+    // "SDR", "HDR10", "HLG", ...
+    pub dynamic_range: Option<String>,
+    // Total bitrate in kbps
+    pub tbr: Option<f64>,
+    pub container: String,
+    // True when the stream has no audio and must be merged with a separate audio stream
+    pub needs_merge: bool,
+    // True for H.264/HEVC in MP4 with AAC audio, which QuickTime plays without conversion
+    pub quicktime_compatible: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioFormat {
+    pub format_id: String,
+    pub ext: String,
+    pub acodec: String,
+    // Average audio bitrate in kbps
+    pub abr: Option<f64>,
+    // Sample rate in Hz
+    pub asr: Option<u64>,
+    pub filesize: Option<u64>,
+    pub language: Option<String>,
+    pub quality_label: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,47 +118,125 @@ pub fn get_app_version() -> String {
 }
 
 #[tauri::command]
-pub async fn get_video_info(url: String) -> Result<VideoInfo, String> {
-    let ytdlp_path = ytdlp_manager::get_ytdlp_path()
-        .await
-        .map_err(|e| format!("Failed to get YT-DLP path: {}", e))?;
-
-    let output = tokio::process::Command::new(&ytdlp_path)
-        .arg("--dump-json")
-        .arg("--no-download")
-        .arg("--no-warnings")
-        .arg(&url)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to execute YT-DLP: {}", e))?;
-
-    if !output.status.success() {
-        let error_msg = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("YT-DLP error: {}", error_msg));
-    }
-
-    let json_output = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Failed to parse YT-DLP output: {}", e))?;
+pub async fn get_video_info(url: String) -> Result<VideoInfo, AppError> {
+    let info = probe::probe_video(&url).await?;
+    Ok(parse_video_info(&info))
+}
 
-    let info: serde_json::Value = serde_json::from_str(&json_output)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+/// Map YT-DLP metadata onto VideoInfo. Missing or mistyped fields become None or empty lists.
+fn parse_video_info(info: &serde_json::Value) -> VideoInfo {
+    let as_string = |key: &str| info[key].as_str().map(|s| s.to_string());
+    let as_u64 = |key: &str| info[key].as_f64().map(|value| value as u64);
+    let as_string_list = |key: &str| -> Vec<String> {
+        info[key]
+            .as_array()
+            .map(|values| values.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default()
+    };
 
-    Ok(VideoInfo {
+    VideoInfo {
+        id: as_string("id"),
+        extractor: as_string("extractor"),
+        webpage_url: as_string("webpage_url"),
         title: info["title"]
             .as_str()
             .unwrap_or("Unknown Title")
             .to_string(),
-        duration: info["duration"].as_u64(),
-        uploader: info["uploader"].as_str().map(|s| s.to_string()),
-        view_count: info["view_count"].as_u64(),
-    })
+        description: as_string("description"),
+        upload_date: as_string("upload_date"),
+        duration: as_u64("duration"),
+        uploader: as_string("uploader"),
+        channel: as_string("channel"),
+        channel_url: as_string("channel_url"),
+        view_count: as_u64("view_count"),
+        like_count: as_u64("like_count"),
+        tags: as_string_list("tags"),
+        categories: as_string_list("categories"),
+        age_limit: as_u64("age_limit"),
+        live_status: as_string("live_status"),
+        availability: as_string("availability"),
+        thumbnails: parse_thumbnails(info),
+        chapters: parse_chapters(info),
+        subtitles: parse_subtitle_tracks(info),
+    }
+}
+
+fn parse_thumbnails(info: &serde_json::Value) -> Vec<Thumbnail> {
+    info["thumbnails"]
+        .as_array()
+        .map(|thumbnails| {
+            thumbnails
+                .iter()
+                .filter_map(|thumbnail| {
+                    Some(Thumbnail {
+                        url: thumbnail["url"].as_str()?.to_string(),
+                        id: thumbnail["id"].as_str().map(|s| s.to_string()),
+                        width: thumbnail["width"].as_u64(),
+                        height: thumbnail["height"].as_u64(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_chapters(info: &serde_json::Value) -> Vec<Chapter> {
+    info["chapters"]
+        .as_array()
+        .map(|chapters| {
+            chapters
+                .iter()
+                .filter_map(|chapter| {
+                    Some(Chapter {
+                        title: chapter["title"].as_str().map(|s| s.to_string()),
+                        start_time: chapter["start_time"].as_f64()?,
+                        end_time: chapter["end_time"].as_f64()?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Collect subtitle languages from YT-DLP's `subtitles` and `automatic_captions` maps
+fn parse_subtitle_tracks(info: &serde_json::Value) -> Vec<SubtitleTrack> {
+    let mut tracks = Vec::new();
+
+    for (key, automatic) in [("subtitles", false), ("automatic_captions", true)] {
+        let Some(languages) = info[key].as_object() else {
+            continue;
+        };
+
+        let mut group: Vec<SubtitleTrack> = languages
+            .iter()
+            // "live_chat" is a replay of the chat, not a subtitle track
+            .filter(|(language, _)| language.as_str() != "live_chat")
+            .map(|(language, entries)| {
+                let entries = entries.as_array().cloned().unwrap_or_default();
+                SubtitleTrack {
+                    language: language.clone(),
+                    name: entries.iter().find_map(|entry| entry["name"].as_str().map(|s| s.to_string())),
+                    automatic,
+                    formats: entries
+                        .iter()
+                        .filter_map(|entry| entry["ext"].as_str().map(|s| s.to_string()))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        group.sort_by(|a, b| a.language.cmp(&b.language));
+        tracks.extend(group);
+    }
+
+    tracks
 }
 
 #[tauri::command]
-pub async fn get_ytdlp_version() -> Result<YtdlpVersionInfo, String> {
+pub async fn get_ytdlp_version() -> Result<YtdlpVersionInfo, AppError> {
     let ytdlp_path = ytdlp_manager::get_ytdlp_path()
         .await
-        .map_err(|e| format!("Failed to get YT-DLP path: {}", e))?;
+        .map_err(|e| AppError::new(ErrorKind::BinaryMissing, format!("Failed to get YT-DLP path: {}", e)))?;
 
     // Check if the path is from system PATH or bundled
     let source = ytdlp_manager::get_ytdlp_source(&ytdlp_path).await;
@@ -85,10 +245,10 @@ pub async fn get_ytdlp_version() -> Result<YtdlpVersionInfo, String> {
         .arg("--version")
         .output()
         .await
-        .map_err(|e| format!("Failed to execute YT-DLP: {}", e))?;
+        .map_err(AppError::spawn_failed)?;
 
     if !output.status.success() {
-        return Err("Failed to get YT-DLP version".to_string());
+        return Err("Failed to get YT-DLP version".into());
     }
 
     let version = String::from_utf8(output.stdout)
@@ -101,31 +261,89 @@ pub async fn get_ytdlp_version() -> Result<YtdlpVersionInfo, String> {
 }
 
 #[tauri::command]
-pub async fn check_ytdlp_update() -> Result<bool, String> {
-    updater::check_update_available().await
+pub async fn get_ffmpeg_version() -> Result<ffmpeg_manager::FfmpegVersionInfo, AppError> {
+    ffmpeg_manager::get_ffmpeg_version().await
+}
+
+#[tauri::command]
+pub async fn get_ffmpeg_managed_dir() -> Result<String, AppError> {
+    let path = ffmpeg_manager::get_managed_ffmpeg_dir()?;
+    Ok(path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub async fn update_ytdlp() -> Result<String, String> {
-    updater::update_ytdlp().await
+pub async fn check_ytdlp_update(refresh: Option<bool>) -> Result<bool, AppError> {
+    updater::check_update_available(refresh.unwrap_or(false)).await.map_err(AppError::from)
 }
 
+/// Forget cached YT-DLP release info, so the next check asks GitHub again
 #[tauri::command]
-pub async fn get_download_location() -> Result<String, String> {
+pub async fn clear_release_cache() -> Result<(), AppError> {
+    release_cache::clear().map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn update_ytdlp(window: tauri::Window) -> Result<String, AppError> {
+    updater::update_ytdlp(&window).await
+}
+
+/// Stop downloading the update in progress
+#[tauri::command]
+pub fn cancel_ytdlp_update() -> Result<(), AppError> {
+    updater::cancel_update().map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_update_settings() -> Result<UpdateSettings, AppError> {
+    Ok(config::load_config().updates)
+}
+
+/// Change the update channel or pin a version. Pinning doesn't install anything by itself.
+#[tauri::command]
+pub async fn set_update_settings(settings: UpdateSettings) -> Result<(), AppError> {
+    settings.validate()?;
+
+    let mut app_config = config::load_config();
+    app_config.updates = UpdateSettings {
+        pinned_version: settings.pinned_version.map(|version| version.trim().to_string()),
+        ..settings
+    };
+    config::save_config(&app_config)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_ytdlp_releases(limit: Option<u32>, refresh: Option<bool>) -> Result<Vec<ReleaseSummary>, AppError> {
+    updater::list_releases(limit, refresh.unwrap_or(false)).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn install_ytdlp_version(version: String, window: tauri::Window) -> Result<String, AppError> {
+    updater::install_version(&version, &window).await
+}
+
+#[tauri::command]
+pub async fn rollback_ytdlp() -> Result<String, AppError> {
+    updater::rollback_ytdlp().await.map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_download_location() -> Result<String, AppError> {
     let path = config::get_download_path()?;
     Ok(path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub async fn set_download_location(path: String) -> Result<(), String> {
+pub async fn set_download_location(path: String) -> Result<(), AppError> {
     let path_buf = PathBuf::from(&path);
     
     if !path_buf.exists() {
-        return Err("Path does not exist".to_string());
+        return Err("Path does not exist".into());
     }
     
     if !path_buf.is_dir() {
-        return Err("Path is not a directory".to_string());
+        return Err("Path is not a directory".into());
     }
     
     let mut app_config = config::load_config();
@@ -136,374 +354,649 @@ pub async fn set_download_location(path: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn get_video_formats(url: String) -> Result<Vec<VideoFormat>, String> {
-    let ytdlp_path = ytdlp_manager::get_ytdlp_path()
-        .await
-        .map_err(|e| format!("Failed to get YT-DLP path: {}", e))?;
+pub async fn get_max_concurrent_downloads() -> Result<usize, AppError> {
+    Ok(config::load_config().max_concurrent_downloads)
+}
 
-    // Use -J to get JSON with formats
-    let output = tokio::process::Command::new(&ytdlp_path)
-        .arg("-J")
-        .arg("--no-warnings")
-        .arg(&url)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to execute YT-DLP: {}", e))?;
+#[tauri::command]
+pub async fn set_max_concurrent_downloads(limit: usize) -> Result<(), AppError> {
+    if limit == 0 {
+        return Err("Concurrent download limit must be at least 1".into());
+    }
 
-    if !output.status.success() {
-        let error_msg = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("YT-DLP error: {}", error_msg));
+    let mut app_config = config::load_config();
+    app_config.max_concurrent_downloads = limit;
+    config::save_config(&app_config)?;
+
+    // Raising the limit can free slots for queued jobs; running jobs are left alone
+    download_manager::start_queued_jobs().map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_retry_policy() -> Result<RetryPolicy, AppError> {
+    Ok(config::load_config().retry)
+}
+
+#[tauri::command]
+pub async fn set_retry_policy(policy: RetryPolicy) -> Result<(), AppError> {
+    policy.validate()?;
+
+    let mut app_config = config::load_config();
+    app_config.retry = policy;
+    config::save_config(&app_config)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_cleanup_policy() -> Result<CleanupPolicy, AppError> {
+    Ok(config::load_config().cleanup)
+}
+
+#[tauri::command]
+pub async fn set_cleanup_policy(policy: CleanupPolicy) -> Result<(), AppError> {
+    let mut app_config = config::load_config();
+    app_config.cleanup = policy;
+    config::save_config(&app_config)?;
+
+    Ok(())
+}
+
+/// List partial and temporary files in the download directory that no download is using.
+/// Only files older than `min_age_hours` (default 24) are listed.
+#[tauri::command]
+pub async fn scan_leftover_files(min_age_hours: Option<u64>) -> Result<Vec<LeftoverFile>, AppError> {
+    let download_dir = config::get_download_path()?;
+    let min_age = std::time::Duration::from_secs(min_age_hours.unwrap_or(24).saturating_mul(60 * 60));
+    let active_files = download_manager::active_files()?;
+
+    cleanup::scan_leftovers(&download_dir, min_age, &active_files).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn delete_leftover_files(paths: Vec<String>) -> Result<(), AppError> {
+    let download_dir = config::get_download_path()?;
+    cleanup::delete_leftovers(&download_dir, &paths).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_output_template() -> Result<String, AppError> {
+    Ok(config::load_config().output_template)
+}
+
+#[tauri::command]
+pub async fn set_output_template(template: String) -> Result<(), AppError> {
+    output_template::validate(&template)?;
+
+    let mut app_config = config::load_config();
+    app_config.output_template = template.trim().to_string();
+    config::save_config(&app_config)?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_output_template_presets() -> Vec<OutputTemplatePreset> {
+    output_template::PRESETS.to_vec()
+}
+
+#[tauri::command]
+pub fn validate_output_template(template: String) -> Result<(), AppError> {
+    output_template::validate(&template).map_err(AppError::from)
+}
+
+/// Render the file path a download of `url` would get, using `template` or the configured one
+#[tauri::command]
+pub async fn preview_output_template(url: String, template: Option<String>) -> Result<OutputTemplatePreview, AppError> {
+    let template = template.unwrap_or_else(|| config::load_config().output_template);
+    let download_dir = config::get_download_path()?;
+    output_template::preview(&url, &template, &download_dir).await
+}
+
+/// Forget cached probe results, e.g. after a video's formats changed
+#[tauri::command]
+pub async fn clear_probe_cache() -> Result<(), AppError> {
+    probe::clear_cache().map_err(AppError::from)
+}
+
+/// Human-readable codec family for a YT-DLP vcodec string (e.g. "avc1.640028" -> "H.264")
+fn video_codec_name(vcodec: &str) -> String {
+    let lower = vcodec.to_lowercase();
+    if lower.starts_with("avc1") || lower.starts_with("h264") {
+        "H.264".to_string()
+    } else if lower.starts_with("hvc1") || lower.starts_with("hev1") || lower.starts_with("h265") {
+        "HEVC".to_string()
+    } else if lower.starts_with("vp09") || lower.starts_with("vp9") {
+        "VP9".to_string()
+    } else if lower.starts_with("av01") {
+        "AV1".to_string()
+    } else {
+        vcodec.split('.').next().unwrap_or(vcodec).to_uppercase()
+    }
+}
+
+/// Parse a video format entry from YT-DLP JSON. Returns None for audio-only formats.
+fn parse_video_format(format: &serde_json::Value) -> Option<VideoFormat> {
+    let vcodec = format["vcodec"].as_str().unwrap_or("unknown").to_string();
+    // Skip audio-only formats
+    if vcodec == "none" {
+        return None;
+    }
+
+    let format_id = format["format_id"]
+        .as_str()
+        .unwrap_or("unknown")
+        .to_string();
+
+    let ext = format["ext"]
+        .as_str()
+        .unwrap_or("unknown")
+        .to_string();
+
+    let acodec = format["acodec"].as_str().unwrap_or("unknown").to_string();
+
+    let resolution = if let Some(res) = format["resolution"].as_str() {
+        res.to_string()
+    } else {
+        // Try to construct from width/height
+        let width = format["width"].as_u64().unwrap_or(0);
+        let height = format["height"].as_u64().unwrap_or(0);
+        if width > 0 && height > 0 {
+            format!("{}x{}", width, height)
+        } else {
+            "unknown".to_string()
+        }
+    };
+
+    let filesize = format["filesize"]
+        .as_u64()
+        .or_else(|| format["filesize_approx"].as_u64());
+
+    let fps = format["fps"].as_f64();
+    let dynamic_range = format["dynamic_range"].as_str().map(|s| s.to_string());
+    let tbr = format["tbr"].as_f64();
+
+    // Video-only streams have to be merged with a separate audio stream
+    let needs_merge = acodec == "none";
+
+    // QuickTime plays H.264/HEVC in MP4 with AAC audio (or no audio yet, when merged with AAC)
+    let codec_name = video_codec_name(&vcodec);
+    let quicktime_compatible = ext == "mp4"
+        && (codec_name == "H.264" || codec_name == "HEVC")
+        && (needs_merge || acodec.starts_with("mp4a"));
+
+    // Get fps if available
+    let fps_str = if let Some(fps_val) = fps {
+        format!(" @ {}fps", fps_val as u32)
+    } else {
+        String::new()
+    };
+
+    let hdr_str = match dynamic_range.as_deref() {
+        Some(range) if range != "SDR" => format!(", {}", range),
+        _ => String::new(),
+    };
+
+    // Create quality label
+    let quality_label = if resolution != "unknown" {
+        format!("{} ({}, {}{}){}", resolution, ext.to_uppercase(), codec_name, hdr_str, fps_str)
+    } else {
+        format!("Format {} ({})", format_id, ext.to_uppercase())
+    };
+
+    Some(VideoFormat {
+        format_id,
+        resolution,
+        ext: ext.clone(),
+        filesize,
+        quality_label,
+        vcodec,
+        acodec,
+        fps,
+        dynamic_range,
+        tbr,
+        container: ext,
+        needs_merge,
+        quicktime_compatible,
+    })
+}
+
+/// List video formats. By default only MP4 formats are listed, one per resolution, since those
+/// merge natively for QuickTime. With `all_formats` every container and codec is listed, with
+/// one entry per resolution, codec, frame rate and dynamic range.
+#[tauri::command]
+pub async fn get_video_formats(url: String, all_formats: Option<bool>) -> Result<Vec<VideoFormat>, AppError> {
+    let all_formats = all_formats.unwrap_or(false);
+    let info = probe::probe_video(&url).await?;
+
+    let formats: Vec<VideoFormat> = info["formats"]
+        .as_array()
+        .map(|formats_array| formats_array.iter().filter_map(parse_video_format).collect())
+        .unwrap_or_default();
+
+    // Deduplicate formats, keeping the best quality (largest filesize, then bitrate) for each key
+    // yt-dlp will automatically pair the selected video with the best available audio
+    use std::collections::HashMap;
+    let mut format_map: HashMap<String, VideoFormat> = HashMap::new();
+
+    for format in formats {
+        let key = if all_formats {
+            format!(
+                "{}|{}|{}|{}",
+                format.resolution,
+                video_codec_name(&format.vcodec),
+                format.fps.map(|fps| fps.round() as u32).unwrap_or(0),
+                format.dynamic_range.as_deref().unwrap_or("SDR")
+            )
+        } else {
+            // Only show MP4 formats in the dropdown for QuickTime compatibility
+            // Skip all other formats (webm, mkv, etc.)
+            if format.ext != "mp4" {
+                continue;
+            }
+            format.resolution.clone()
+        };
+
+        match format_map.get_mut(&key) {
+            Some(existing) => {
+                let existing_rank = (existing.filesize.unwrap_or(0), existing.tbr.unwrap_or(0.0));
+                let new_rank = (format.filesize.unwrap_or(0), format.tbr.unwrap_or(0.0));
+                if new_rank > existing_rank {
+                    *existing = format;
+                }
+            }
+            None => {
+                format_map.insert(key, format);
+            }
+        }
     }
 
-    let json_output = String::from_utf8(output.stdout)
-        .map_err(|e| format!("Failed to parse YT-DLP output: {}", e))?;
+    // Convert back to vector
+    let mut deduped_formats: Vec<VideoFormat> = format_map.into_values().collect();
+
+    // Sort by resolution (best first), then frame rate, then bitrate
+    deduped_formats.sort_by(|a, b| {
+        // Extract numeric resolution for sorting
+        let resolution_key = |format: &VideoFormat| -> (u32, u32) {
+            let mut parts = format.resolution.split('x').map(|s| s.parse().unwrap_or(0));
+            let width = parts.next().unwrap_or(0);
+            let height = parts.next().unwrap_or(0);
+            (height, width)
+        };
+        resolution_key(b)
+            .cmp(&resolution_key(a))
+            .then(b.fps.unwrap_or(0.0).total_cmp(&a.fps.unwrap_or(0.0)))
+            .then(b.tbr.unwrap_or(0.0).total_cmp(&a.tbr.unwrap_or(0.0)))
+    });
+
+    Ok(deduped_formats)
+}
+
+/// List a page of playlist or channel entries using flat extraction
+#[tauri::command]
+pub async fn get_playlist_entries(url: String, start: Option<u64>, count: Option<u64>) -> Result<PlaylistPage, AppError> {
+    playlist::get_entries(&url, start, count).await
+}
+
+/// Stream every playlist entry as a "playlist-entry" event; returns the probe ID
+#[tauri::command]
+pub async fn start_playlist_probe(url: String, window: tauri::Window) -> Result<ProbeId, AppError> {
+    playlist::start_probe(&url, window).await
+}
+
+#[tauri::command]
+pub async fn cancel_playlist_probe(probe_id: ProbeId) -> Result<(), AppError> {
+    playlist::cancel_probe(probe_id).map_err(AppError::from)
+}
 
-    let info: serde_json::Value = serde_json::from_str(&json_output)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+#[tauri::command]
+pub async fn get_audio_formats(url: String) -> Result<Vec<AudioFormat>, AppError> {
+    let info = probe::probe_video(&url).await?;
 
     let mut formats = Vec::new();
 
-    // Parse formats from JSON
     if let Some(formats_array) = info["formats"].as_array() {
         for format in formats_array {
-            // Skip audio-only formats
-            if format["vcodec"].as_str() == Some("none") {
+            // Only audio-only formats: no video stream, but an audio one
+            if format["vcodec"].as_str() != Some("none") {
                 continue;
             }
-            
+            let acodec = match format["acodec"].as_str() {
+                Some(acodec) if acodec != "none" => acodec.to_string(),
+                _ => continue,
+            };
+
             let format_id = format["format_id"]
                 .as_str()
                 .unwrap_or("unknown")
                 .to_string();
-            
+
             let ext = format["ext"]
                 .as_str()
                 .unwrap_or("unknown")
                 .to_string();
-            
-            // Only show MP4 formats in the dropdown for QuickTime compatibility
-            // Skip all other formats (webm, mkv, etc.)
-            if ext != "mp4" {
-                continue;
-            }
-            
-            let resolution = if let Some(res) = format["resolution"].as_str() {
-                res.to_string()
-            } else {
-                // Try to construct from width/height
-                let width = format["width"].as_u64().unwrap_or(0);
-                let height = format["height"].as_u64().unwrap_or(0);
-                if width > 0 && height > 0 {
-                    format!("{}x{}", width, height)
-                } else {
-                    "unknown".to_string()
-                }
-            };
-            
+
+            let abr = format["abr"].as_f64();
+            let asr = format["asr"].as_u64();
             let filesize = format["filesize"]
                 .as_u64()
                 .or_else(|| format["filesize_approx"].as_u64());
-            
-            // Get fps if available
-            let fps = format["fps"].as_f64();
-            let fps_str = if let Some(fps_val) = fps {
-                format!(" @ {}fps", fps_val as u32)
-            } else {
-                String::new()
-            };
-            
-            // Create quality label
-            let quality_label = if resolution != "unknown" {
-                format!("{} ({}){}", resolution, ext.to_uppercase(), fps_str)
-            } else {
-                format!("Format {} ({})", format_id, ext.to_uppercase())
+            let language = format["language"].as_str().map(|s| s.to_string());
+
+            // Create quality label, e.g. "160kbps OPUS (en)"
+            let mut quality_label = match abr {
+                Some(abr) => format!("{}kbps {}", abr.round() as u32, ext.to_uppercase()),
+                None => format!("Format {} ({})", format_id, ext.to_uppercase()),
             };
+            if let Some(language) = &language {
+                quality_label.push_str(&format!(" ({})", language));
+            }
 
-            formats.push(VideoFormat {
+            formats.push(AudioFormat {
                 format_id,
-                resolution,
                 ext,
+                acodec,
+                abr,
+                asr,
                 filesize,
+                language,
                 quality_label,
             });
         }
     }
 
-    // Deduplicate formats based on resolution only
-    // Keep the format with the best quality (largest filesize) for each unique resolution
-    // yt-dlp will automatically pair the selected video with the best available audio
-    use std::collections::HashMap;
-    let mut format_map: HashMap<String, VideoFormat> = HashMap::new();
-    
-    for format in formats {
-        // Use resolution as the unique key
-        let key = format.resolution.clone();
-        
-        // If we haven't seen this resolution, add it
-        // If we have, keep the one with larger filesize (better quality)
-        match format_map.get_mut(&key) {
-            Some(existing) => {
-                // Compare filesizes - keep the larger one
-                let existing_size = existing.filesize.unwrap_or(0);
-                let new_size = format.filesize.unwrap_or(0);
-                if new_size > existing_size {
-                    *existing = format;
-                }
-            }
-            None => {
-                format_map.insert(key, format);
-            }
-        }
-    }
-    
-    // Convert back to vector
-    let mut deduped_formats: Vec<VideoFormat> = format_map.into_values().collect();
-    
-    // Sort by resolution (best first) - all formats are MP4 for QuickTime compatibility
-    deduped_formats.sort_by(|a, b| {
-        // Extract numeric resolution for sorting
-        let a_res: u32 = a.resolution.split('x').next()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0);
-        let b_res: u32 = b.resolution.split('x').next()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0);
-        b_res.cmp(&a_res)
+    // Highest bitrate first
+    formats.sort_by(|a, b| {
+        b.abr.unwrap_or(0.0)
+            .partial_cmp(&a.abr.unwrap_or(0.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    Ok(deduped_formats)
+    Ok(formats)
+}
+
+#[tauri::command]
+pub async fn download_video(
+    url: String,
+    quality: Option<String>,
+    options: Option<DownloadOptions>,
+    window: tauri::Window,
+) -> Result<JobId, AppError> {
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] download_video called with url: {}, quality: {:?}, options: {:?}", url, quality, options);
+
+    download_manager::enqueue(url, quality, options.unwrap_or_default(), window).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn cancel_download(job_id: JobId) -> Result<(), AppError> {
+    download_manager::cancel(job_id).map_err(AppError::from)
+}
+
+/// Stop a download, keeping its partial files so it can be resumed
+#[tauri::command]
+pub async fn pause_download(job_id: JobId) -> Result<(), AppError> {
+    download_manager::pause(job_id).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn resume_download(job_id: JobId) -> Result<(), AppError> {
+    download_manager::resume(job_id).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn list_downloads() -> Result<Vec<DownloadJob>, AppError> {
+    download_manager::list().map_err(AppError::from)
+}
+
+/// Downloads the previous session left unfinished
+#[tauri::command]
+pub async fn list_interrupted_downloads() -> Result<Vec<DownloadJob>, AppError> {
+    download_manager::list_interrupted().map_err(AppError::from)
 }
 
-// Global state to store the cancel sender for cancellation
-static CANCEL_SENDER: Mutex<Option<oneshot::Sender<()>>> = Mutex::new(None);
+/// Queue interrupted downloads again, reusing their partial files. Returns the new job IDs.
+#[tauri::command]
+pub async fn resume_interrupted_downloads(ids: Vec<JobId>, window: tauri::Window) -> Result<Vec<JobId>, AppError> {
+    download_manager::resume_interrupted(&ids, window).map_err(AppError::from)
+}
 
 #[tauri::command]
-pub async fn cancel_download() -> Result<(), String> {
-    let mut sender_guard = CANCEL_SENDER.lock().map_err(|e| format!("Lock error: {}", e))?;
-    if let Some(sender) = sender_guard.take() {
-        let _ = sender.send(());
+pub async fn discard_interrupted_downloads(ids: Vec<JobId>) -> Result<(), AppError> {
+    download_manager::discard_interrupted(&ids).map_err(AppError::from)
+}
+
+/// Check that a path from the frontend is a file a download produced, as recorded by its job or in
+/// the history. Anything else could be an executable the system would happily launch.
+fn downloaded_file_path(path: &str) -> Result<PathBuf, AppError> {
+    let path_buf = PathBuf::from(path);
+    let canonical = path_buf
+        .canonicalize()
+        .map_err(|_| AppError::from("File does not exist"))?;
+
+    let mut known_files = download_manager::downloaded_files()?;
+    known_files.extend(history::file_paths()?);
+
+    let is_known = known_files
+        .iter()
+        .any(|known| Path::new(known).canonicalize().is_ok_and(|known| known == canonical));
+    if !is_known {
+        return Err(format!("Not a downloaded file: {}", path).into());
     }
+
+    Ok(path_buf)
+}
+
+/// Open a downloaded file with the system's default application
+#[tauri::command]
+pub async fn open_downloaded_file(path: String) -> Result<(), AppError> {
+    let path_buf = downloaded_file_path(&path)?;
+
+    let mut cmd = if cfg!(target_os = "windows") {
+        // Explorer opens files with their default application. The path comes from the video
+        // title, so it must never go through cmd, which would interpret & ^ | as commands.
+        let mut cmd = std::process::Command::new("explorer");
+        cmd.arg(&path_buf);
+        cmd
+    } else if cfg!(target_os = "macos") {
+        let mut cmd = std::process::Command::new("open");
+        cmd.arg(&path_buf);
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new("xdg-open");
+        cmd.arg(&path_buf);
+        cmd
+    };
+
+    cmd.spawn()
+        .map_err(|e| format!("Failed to open file: {}", e))?;
+
     Ok(())
 }
 
+/// Show a downloaded file in the system file manager
 #[tauri::command]
-pub async fn download_video(url: String, quality: Option<String>, window: tauri::Window) -> Result<String, String> {
-    #[cfg(debug_assertions)]
-    eprintln!("[DEBUG] download_video called with url: {}, quality: {:?}", url, quality);
-    
-    let ytdlp_path = ytdlp_manager::get_ytdlp_path()
-        .await
-        .map_err(|e| {
-            #[cfg(debug_assertions)]
-            eprintln!("[DEBUG] Failed to get YT-DLP path: {}", e);
-            format!("Failed to get YT-DLP path: {}", e)
-        })?;
-    
-    #[cfg(debug_assertions)]
-    eprintln!("[DEBUG] YT-DLP path: {:?}", ytdlp_path);
-    
-    let download_dir = config::get_download_path()
-        .map_err(|e| {
-            #[cfg(debug_assertions)]
-            eprintln!("[DEBUG] Failed to get download path: {}", e);
-            e
-        })?;
-    
-    #[cfg(debug_assertions)]
-    eprintln!("[DEBUG] Download directory: {:?}", download_dir);
-    
-    let mut cmd = tokio::process::Command::new(&ytdlp_path);
-    cmd.arg("--output")
-        .arg(format!("{}/%(title)s.%(ext)s", download_dir.to_string_lossy()))
-        .arg("--newline")
-        .arg("--progress")
-        .arg("--no-warnings")
-        .arg("--color")  // Force color output even when not in TTY
-        .arg("always")   // Color policy: always output colors
-        // Force MP4 output format for QuickTime compatibility
-        // YT-DLP will use native muxer for MP4 (no FFmpeg required)
-        .arg("--merge-output-format")
-        .arg("mp4");
-    
-    // Add quality selector if specified
-    // Format selectors only use MP4 formats that can be merged natively without FFmpeg
-    // YT-DLP's native muxer can merge MP4 video + MP4 audio streams
-    if let Some(quality) = quality {
-        if quality == "best" || quality == "worst" {
-            // For best/worst, prefer H.264+AAC MP4 for QuickTime compatibility
-            // Only select formats that are already MP4 (no re-encoding needed)
-            // Format selector: prefer H.264 video + AAC audio in MP4, fallback to any MP4
-            let format_selector = if quality == "best" {
-                "bestvideo[ext=mp4][vcodec^=avc1]+bestaudio[ext=mp4][acodec^=mp4a]/bestvideo[ext=mp4]+bestaudio[ext=mp4]/best[ext=mp4]"
-            } else {
-                "worstvideo[ext=mp4][vcodec^=avc1]+worstaudio[ext=mp4][acodec^=mp4a]/worstvideo[ext=mp4]+worstaudio[ext=mp4]/worst[ext=mp4]"
-            };
-            cmd.arg("-f").arg(format_selector);
-        } else {
-            // For specific format ID (resolution), pair it with best MP4 audio
-            // Only use MP4 formats to avoid needing FFmpeg
-            cmd.arg("-f").arg(format!("{}+bestaudio[ext=mp4]/best[ext=mp4]", quality));
-        }
+pub async fn reveal_downloaded_file(path: String) -> Result<(), AppError> {
+    let path_buf = downloaded_file_path(&path)?;
+
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = std::process::Command::new("explorer");
+        cmd.arg(format!("/select,{}", path_buf.to_string_lossy()));
+        cmd
+    } else if cfg!(target_os = "macos") {
+        let mut cmd = std::process::Command::new("open");
+        cmd.arg("-R").arg(&path_buf);
+        cmd
     } else {
-        // Default: prefer H.264+AAC MP4 for QuickTime compatibility
-        // Only select MP4 formats that can be merged natively
-        cmd.arg("-f").arg("bestvideo[ext=mp4][vcodec^=avc1]+bestaudio[ext=mp4][acodec^=mp4a]/bestvideo[ext=mp4]+bestaudio[ext=mp4]/best[ext=mp4]");
+        // There is no portable way to select a file on Linux, so open its folder instead
+        let folder = path_buf.parent().map(|p| p.to_path_buf()).unwrap_or(path_buf);
+        let mut cmd = std::process::Command::new("xdg-open");
+        cmd.arg(folder);
+        cmd
+    };
+
+    cmd.spawn()
+        .map_err(|e| format!("Failed to reveal file: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_history(filter: Option<HistoryFilter>) -> Result<Vec<HistoryEntry>, AppError> {
+    history::list_entries(&filter.unwrap_or_default()).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn delete_history_entries(ids: Vec<u64>) -> Result<(), AppError> {
+    history::delete_entries(&ids).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn clear_history() -> Result<(), AppError> {
+    history::clear().map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_manual_subtitles_before_automatic_captions() {
+        let info = serde_json::json!({
+            "subtitles": {
+                "fr": [{ "ext": "vtt", "name": "French" }],
+                "en": [{ "ext": "vtt", "name": "English" }, { "ext": "srt" }],
+                "live_chat": [{ "ext": "json" }]
+            },
+            "automatic_captions": {
+                "de": [{ "ext": "vtt", "name": "German (auto-generated)" }]
+            }
+        });
+
+        let tracks = parse_subtitle_tracks(&info);
+        let summary: Vec<_> = tracks
+            .iter()
+            .map(|track| (track.language.as_str(), track.automatic))
+            .collect();
+        assert_eq!(summary, [("en", false), ("fr", false), ("de", true)]);
+
+        assert_eq!(tracks[0].name.as_deref(), Some("English"));
+        assert_eq!(tracks[0].formats, ["vtt", "srt"]);
     }
-    
-    cmd.arg(&url)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
-    
-    #[cfg(debug_assertions)]
-    eprintln!("[DEBUG] Executing YT-DLP command...");
-    
-    let mut child = cmd.spawn()
-        .map_err(|e| {
-            #[cfg(debug_assertions)]
-            eprintln!("[DEBUG] Failed to spawn YT-DLP process: {}", e);
-            format!("Failed to execute YT-DLP: {}", e)
-        })?;
-    
-    #[cfg(debug_assertions)]
-    eprintln!("[DEBUG] YT-DLP process spawned successfully");
-    
-    // Create cancellation channel
-    let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
-    {
-        let mut sender_guard = CANCEL_SENDER.lock().map_err(|e| format!("Lock error: {}", e))?;
-        *sender_guard = Some(cancel_tx);
+
+    #[test]
+    fn no_subtitle_maps_means_no_tracks() {
+        assert!(parse_subtitle_tracks(&serde_json::json!({ "id": "abc" })).is_empty());
+        assert!(parse_subtitle_tracks(&serde_json::json!({ "subtitles": null })).is_empty());
     }
-    
-    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-    
-    use tokio::io::{AsyncBufReadExt, BufReader};
-    let mut stdout_reader = BufReader::new(stdout);
-    let mut stderr_reader = BufReader::new(stderr);
-    
-    // Capture console output from YT-DLP
-    // Create a cancellation channel for the progress task
-    let (progress_cancel_tx, mut progress_cancel_rx) = oneshot::channel::<()>();
-    
-    let window_clone = window.clone();
-    let progress_task = tokio::spawn(async move {
-        let mut stdout_buf = Vec::new();
-        let mut stderr_buf = Vec::new();
-        
-        loop {
-            tokio::select! {
-                _ = &mut progress_cancel_rx => {
-                    // Cancellation requested - stop processing output
-                    break;
-                }
-                result = stdout_reader.read_until(b'\n', &mut stdout_buf) => {
-                    match result {
-                        Ok(0) => {
-                            // EOF
-                            #[cfg(debug_assertions)]
-                            eprintln!("[DEBUG] stdout stream ended");
-                            break;
-                        }
-                        Ok(_) => {
-                            // Convert bytes to string using lossy UTF-8 conversion
-                            // This handles progress indicators and special characters gracefully
-                            let line = String::from_utf8_lossy(&stdout_buf);
-                            let line = line.trim_end_matches('\n').trim_end_matches('\r');
-                            
-                            if !line.is_empty() {
-                                #[cfg(debug_assertions)]
-                                eprintln!("[DEBUG] YT-DLP stdout: {}", line);
-                                // Emit the line to frontend
-                                let _ = window_clone.emit("download-output", line.to_string());
-                            }
-                            
-                            stdout_buf.clear();
-                        }
-                        Err(e) => {
-                            #[cfg(debug_assertions)]
-                            eprintln!("[DEBUG] stdout read error: {:?}", e);
-                            // Continue reading on errors - don't break the download
-                            if e.kind() != std::io::ErrorKind::Interrupted {
-                                break;
-                            }
-                        }
-                    }
-                }
-                result = stderr_reader.read_until(b'\n', &mut stderr_buf) => {
-                    match result {
-                        Ok(0) => {
-                            // EOF
-                            #[cfg(debug_assertions)]
-                            eprintln!("[DEBUG] stderr stream ended");
-                            break;
-                        }
-                        Ok(_) => {
-                            // Convert bytes to string using lossy UTF-8 conversion
-                            // This handles progress indicators and special characters gracefully
-                            let line = String::from_utf8_lossy(&stderr_buf);
-                            let line = line.trim_end_matches('\n').trim_end_matches('\r');
-                            
-                            if !line.is_empty() {
-                                #[cfg(debug_assertions)]
-                                eprintln!("[DEBUG] YT-DLP stderr: {}", line);
-                                // Emit the line to frontend (YT-DLP often uses stderr for progress)
-                                let _ = window_clone.emit("download-output", line.to_string());
-                            }
-                            
-                            stderr_buf.clear();
-                        }
-                        Err(e) => {
-                            #[cfg(debug_assertions)]
-                            eprintln!("[DEBUG] stderr read error: {:?}", e);
-                            // Continue reading on errors - don't break the download
-                            if e.kind() != std::io::ErrorKind::Interrupted {
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    });
-    
-    // Wait for process or cancellation
-    let status = tokio::select! {
-        result = child.wait() => {
-            result.map_err(|e| format!("Failed to wait for process: {}", e))?
-        }
-        _ = &mut cancel_rx => {
-            // Cancellation requested - kill the process
-            let _ = child.kill().await;
-            let _ = child.wait().await;
-            // Clear the cancel sender
-            {
-                let mut sender_guard = CANCEL_SENDER.lock().map_err(|e| format!("Lock error: {}", e))?;
-                *sender_guard = None;
-            }
-            // Cancel progress task
-            let _ = progress_cancel_tx.send(());
-            let _ = progress_task.await;
-            return Err("Download cancelled".to_string());
-        }
-    };
-    
-    // Cancel progress task and wait for it to finish
-    let _ = progress_cancel_tx.send(());
-    let _ = progress_task.await;
-    
-    // Clear the cancel sender
-    {
-        let mut sender_guard = CANCEL_SENDER.lock().map_err(|e| format!("Lock error: {}", e))?;
-        *sender_guard = None;
+
+    #[test]
+    fn parses_video_format_details() {
+        let format = parse_video_format(&serde_json::json!({
+            "format_id": "137",
+            "ext": "mp4",
+            "vcodec": "avc1.640028",
+            "acodec": "none",
+            "width": 1920,
+            "height": 1080,
+            "fps": 30.0,
+            "dynamic_range": "SDR",
+            "filesize_approx": 1000
+        }))
+        .unwrap();
+
+        assert_eq!(format.resolution, "1920x1080");
+        assert_eq!(format.filesize, Some(1000));
+        assert_eq!(format.quality_label, "1920x1080 (MP4, H.264) @ 30fps");
+        assert!(format.needs_merge);
+        assert!(format.quicktime_compatible);
     }
-    
-    if !status.success() {
-        return Err("Download failed".to_string());
+
+    #[test]
+    fn skips_audio_only_formats() {
+        let format = serde_json::json!({ "format_id": "140", "ext": "m4a", "vcodec": "none", "acodec": "mp4a.40.2" });
+        assert!(parse_video_format(&format).is_none());
+    }
+
+    #[test]
+    fn only_h264_and_hevc_mp4_with_aac_are_quicktime_compatible() {
+        let compatible = |ext: &str, vcodec: &str, acodec: &str| {
+            let format = serde_json::json!({ "format_id": "1", "ext": ext, "vcodec": vcodec, "acodec": acodec });
+            parse_video_format(&format).unwrap().quicktime_compatible
+        };
+
+        assert!(compatible("mp4", "avc1.4d401f", "mp4a.40.2"));
+        assert!(compatible("mp4", "hvc1.2.4.L153", "none"));
+        assert!(!compatible("mp4", "av01.0.08M.08", "none"));
+        assert!(!compatible("mp4", "avc1.4d401f", "opus"));
+        assert!(!compatible("webm", "vp9", "opus"));
+        assert!(!compatible("mkv", "avc1.640028", "mp4a.40.2"));
+    }
+
+    #[test]
+    fn labels_hdr_and_unknown_resolution() {
+        let hdr = serde_json::json!({
+            "format_id": "337", "ext": "webm", "vcodec": "vp09.02.51.10", "acodec": "none",
+            "resolution": "3840x2160", "dynamic_range": "HDR10"
+        });
+        assert_eq!(parse_video_format(&hdr).unwrap().quality_label, "3840x2160 (WEBM, VP9, HDR10)");
+
+        let unknown = serde_json::json!({ "format_id": "sb0", "ext": "mhtml", "vcodec": "images" });
+        let format = parse_video_format(&unknown).unwrap();
+        assert_eq!(format.resolution, "unknown");
+        assert_eq!(format.quality_label, "Format sb0 (MHTML)");
+    }
+
+    #[test]
+    fn maps_video_metadata() {
+        let info = parse_video_info(&serde_json::json!({
+            "id": "abc",
+            "extractor": "youtube",
+            "title": "Title",
+            "upload_date": "20240310",
+            "duration": 212.5,
+            "uploader": "Uploader",
+            "view_count": 1000,
+            "tags": ["music", 42, "live"],
+            "age_limit": 0,
+            "live_status": "not_live",
+            "thumbnails": [
+                { "url": "https://i.ytimg.com/a.jpg", "id": "0", "width": 120, "height": 90 },
+                { "id": "no-url" }
+            ],
+            "chapters": [
+                { "title": "Intro", "start_time": 0.0, "end_time": 30.0 },
+                { "title": "Broken", "start_time": 30.0 }
+            ]
+        }));
+
+        assert_eq!(info.id.as_deref(), Some("abc"));
+        assert_eq!(info.extractor.as_deref(), Some("youtube"));
+        assert_eq!(info.title, "Title");
+        assert_eq!(info.upload_date.as_deref(), Some("20240310"));
+        assert_eq!(info.duration, Some(212));
+        assert_eq!(info.uploader.as_deref(), Some("Uploader"));
+        assert_eq!(info.view_count, Some(1000));
+        assert_eq!(info.tags, ["music", "live"]);
+        assert_eq!(info.age_limit, Some(0));
+        assert_eq!(info.live_status.as_deref(), Some("not_live"));
+
+        assert_eq!(info.thumbnails.len(), 1);
+        assert_eq!(info.thumbnails[0].width, Some(120));
+        assert_eq!(info.chapters.len(), 1);
+        assert_eq!(info.chapters[0].title.as_deref(), Some("Intro"));
     }
-    
-    // Emit completion message
-    let _ = window.emit("download-output", "Download completed successfully".to_string());
-    
-    Ok(format!("Download completed to: {}", download_dir.to_string_lossy()))
-}
 
+    #[test]
+    fn missing_metadata_stays_empty() {
+        let info = parse_video_info(&serde_json::json!({ "id": "abc", "duration": "long" }));
+
+        assert_eq!(info.title, "Unknown Title");
+        assert_eq!(info.duration, None);
+        assert_eq!(info.uploader, None);
+        assert!(info.tags.is_empty());
+        assert!(info.thumbnails.is_empty());
+        assert!(info.chapters.is_empty());
+        assert!(info.subtitles.is_empty());
+    }
+}
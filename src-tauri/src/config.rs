@@ -1,23 +1,176 @@
 use std::fs;
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
+use crate::output_template;
 
 const CONFIG_FILE_NAME: &str = "config.json";
+const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 3;
+// Longest wait allowed between two download attempts
+const MAX_RETRY_DELAY_SECS: u64 = 3600;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
     pub download_location: Option<String>,
+    // Maximum number of YT-DLP download processes running at once
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+    // How failed downloads are retried
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    // What happens to partial files when a download stops early
+    #[serde(default)]
+    pub cleanup: CleanupPolicy,
+    // YT-DLP output template, relative to the download location
+    #[serde(default = "default_output_template")]
+    pub output_template: String,
+    // Where YT-DLP updates come from
+    #[serde(default)]
+    pub updates: UpdateSettings,
+}
+
+fn default_output_template() -> String {
+    output_template::DEFAULT_OUTPUT_TEMPLATE.to_string()
+}
+
+/// YT-DLP release channel, each published from its own GitHub repository
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    // Daily builds with the latest extractor fixes
+    Nightly,
+    // Built from every commit to master
+    Master,
+}
+
+impl UpdateChannel {
+    pub fn repository(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "yt-dlp/yt-dlp",
+            UpdateChannel::Nightly => "yt-dlp/yt-dlp-nightly-builds",
+            UpdateChannel::Master => "yt-dlp/yt-dlp-master-builds",
+        }
+    }
+}
+
+/// Update channel and an optional pinned version. While a version is pinned,
+/// update checks only report an update when the installed version differs from it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct UpdateSettings {
+    pub channel: UpdateChannel,
+    pub pinned_version: Option<String>,
+}
+
+impl UpdateSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(version) = &self.pinned_version {
+            validate_version(version)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check a YT-DLP version before it becomes part of a GitHub release URL, so it can only name a tag
+pub fn validate_version(version: &str) -> Result<(), String> {
+    let version = version.trim();
+    if version.is_empty() || version.contains(['/', '\\', '?', '#', '%']) || version.contains("..") {
+        return Err(format!("Invalid YT-DLP version: '{}'", version));
+    }
+    Ok(())
+}
+
+/// Whether partial files (.part, .ytdl, unmerged streams) are deleted when a download stops early.
+/// Kept files let the download continue from where it stopped when it is started again.
+/// Paused downloads always keep theirs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CleanupPolicy {
+    pub delete_on_cancel: bool,
+    pub delete_on_failure: bool,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        CleanupPolicy {
+            delete_on_cancel: true,
+            delete_on_failure: false,
+        }
+    }
+}
+
+/// Retry policy for downloads that fail with a transient error (network resets, HTTP 5xx, throttling)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RetryPolicy {
+    // Total number of runs, including the first; 1 disables retrying
+    pub max_attempts: u32,
+    // Delay before the first retry, doubled (by `backoff_factor`) for each retry after it
+    pub initial_delay_secs: u64,
+    pub backoff_factor: f64,
+    pub max_delay_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay_secs: 5,
+            backoff_factor: 2.0,
+            max_delay_secs: 60,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the given retry (1 for the first retry)
+    pub fn delay(&self, retry: u32) -> std::time::Duration {
+        let exponent = i32::try_from(retry.saturating_sub(1)).unwrap_or(i32::MAX);
+        let factor = self.backoff_factor.max(1.0).powi(exponent);
+        // Also capped here, as a hand-edited config file never went through validate()
+        let max_delay = self.max_delay_secs.min(MAX_RETRY_DELAY_SECS) as f64;
+        let delay = (self.initial_delay_secs as f64 * factor).min(max_delay);
+        std::time::Duration::try_from_secs_f64(delay.max(0.0))
+            .unwrap_or(std::time::Duration::from_secs(MAX_RETRY_DELAY_SECS))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 {
+            return Err("Retry attempts must be at least 1".to_string());
+        }
+        if !self.backoff_factor.is_finite() || self.backoff_factor < 1.0 {
+            return Err("Backoff factor must be at least 1".to_string());
+        }
+        if self.max_delay_secs > MAX_RETRY_DELAY_SECS {
+            return Err(format!("Maximum retry delay can't exceed {} seconds", MAX_RETRY_DELAY_SECS));
+        }
+        if self.initial_delay_secs > self.max_delay_secs {
+            return Err("Initial retry delay can't exceed the maximum delay".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_max_concurrent_downloads() -> usize {
+    DEFAULT_MAX_CONCURRENT_DOWNLOADS
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         AppConfig {
             download_location: None,
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            retry: RetryPolicy::default(),
+            cleanup: CleanupPolicy::default(),
+            output_template: default_output_template(),
+            updates: UpdateSettings::default(),
         }
     }
 }
 
-fn get_config_path() -> Result<PathBuf, String> {
+/// App config directory, shared with other files the app persists (e.g. download history)
+pub fn get_config_dir() -> Result<PathBuf, String> {
     let config_dir = dirs::config_dir()
         .ok_or_else(|| "Failed to get config directory".to_string())?
         .join("mac-ytdlp");
@@ -26,7 +179,11 @@ fn get_config_path() -> Result<PathBuf, String> {
     fs::create_dir_all(&config_dir)
         .map_err(|e| format!("Failed to create config directory: {}", e))?;
     
-    Ok(config_dir.join(CONFIG_FILE_NAME))
+    Ok(config_dir)
+}
+
+fn get_config_path() -> Result<PathBuf, String> {
+    Ok(get_config_dir()?.join(CONFIG_FILE_NAME))
 }
 
 pub fn load_config() -> AppConfig {
@@ -74,3 +231,66 @@ pub fn get_download_path() -> Result<PathBuf, String> {
         .ok_or_else(|| "Failed to get Downloads directory".to_string())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_release_versions() {
+        for version in ["2024.03.10", "nightly@2024.03.10.232833", "v2024.03.10", " 2024.03.10 "] {
+            assert_eq!(validate_version(version), Ok(()), "{}", version);
+        }
+    }
+
+    #[test]
+    fn rejects_versions_that_leave_the_tag_path() {
+        for version in ["", "  ", "../latest", "..", "2024/03", "a\\b", "latest?x=1", "1#x", "%2e%2e"] {
+            assert!(validate_version(version).is_err(), "{}", version);
+        }
+    }
+
+    use std::time::Duration;
+
+    #[test]
+    fn retry_delay_grows_by_backoff_factor() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(1), Duration::from_secs(5));
+        assert_eq!(policy.delay(2), Duration::from_secs(10));
+        assert_eq!(policy.delay(3), Duration::from_secs(20));
+        // Capped at max_delay_secs
+        assert_eq!(policy.delay(10), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn retry_delay_never_overflows() {
+        let policy = RetryPolicy {
+            max_attempts: u32::MAX,
+            initial_delay_secs: u64::MAX,
+            backoff_factor: f64::MAX,
+            max_delay_secs: u64::MAX,
+        };
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(MAX_RETRY_DELAY_SECS));
+
+        let policy = RetryPolicy {
+            backoff_factor: f64::NAN,
+            ..RetryPolicy::default()
+        };
+        assert!(policy.delay(3) <= Duration::from_secs(MAX_RETRY_DELAY_SECS));
+    }
+
+    #[test]
+    fn rejects_invalid_retry_policies() {
+        assert_eq!(RetryPolicy::default().validate(), Ok(()));
+
+        let invalid = [
+            RetryPolicy { max_attempts: 0, ..RetryPolicy::default() },
+            RetryPolicy { backoff_factor: 0.5, ..RetryPolicy::default() },
+            RetryPolicy { backoff_factor: f64::INFINITY, ..RetryPolicy::default() },
+            RetryPolicy { max_delay_secs: MAX_RETRY_DELAY_SECS + 1, ..RetryPolicy::default() },
+            RetryPolicy { initial_delay_secs: 61, max_delay_secs: 60, ..RetryPolicy::default() },
+        ];
+        for policy in invalid {
+            assert!(policy.validate().is_err(), "{:?}", policy);
+        }
+    }
+}
@@ -0,0 +1,925 @@
+use crate::cleanup;
+use crate::config;
+use crate::download_options::{self, DownloadOptions};
+use crate::error::AppError;
+use crate::history::{self, HistoryEntry, HistoryOutcome};
+use crate::probe;
+use crate::queue;
+use crate::ytdlp_manager;
+use crate::ytdlp_output::{self, OutputLine, PrintedVideo};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Emitter;
+use tokio::sync::oneshot;
+
+pub type JobId = u64;
+
+// Console lines kept per attempt
+const MAX_ATTEMPT_OUTPUT_LINES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    // Stopped by the user with its partial files kept, until resumed
+    Paused,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadJob {
+    pub id: JobId,
+    pub url: String,
+    pub quality: Option<String>,
+    pub options: DownloadOptions,
+    // Fixed when the job is enqueued, so a resumed job finds its partial files
+    pub download_dir: Option<String>,
+    pub state: JobState,
+    // Completion message on success, error message on failure
+    pub message: Option<String>,
+    // Classified failure, set when the job failed
+    pub error: Option<AppError>,
+    pub result: Option<DownloadResult>,
+    // One entry per YT-DLP run, including retries
+    pub attempts: Vec<JobAttempt>,
+    // Files YT-DLP reported downloading, used to find partial files to clean up
+    pub files: Vec<String>,
+}
+
+/// A single YT-DLP run of a job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobAttempt {
+    // 1 for the first run
+    pub attempt: u32,
+    // Unix timestamps in seconds
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub error: Option<AppError>,
+    // Console output of the run without progress lines, truncated to the last lines
+    pub output: Vec<String>,
+}
+
+/// Where a finished download ended up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadResult {
+    pub download_dir: String,
+    // Final paths after YT-DLP moved the files into place
+    pub file_paths: Vec<String>,
+}
+
+/// A single line of console output from a job's YT-DLP process
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadOutput {
+    pub job_id: JobId,
+    pub line: String,
+}
+
+/// What YT-DLP printed about the download while it ran
+#[derive(Debug, Clone, Default)]
+struct JobReport {
+    // One per video YT-DLP started, in order; playlists have several
+    items: Vec<ReportItem>,
+    // Final paths of the files YT-DLP produced, in the order they were moved into place
+    file_paths: Vec<String>,
+    // ERROR lines YT-DLP printed, used to classify a failure
+    errors: Vec<String>,
+    // Recent console output, for the attempt record
+    output: Vec<String>,
+    // Files named in progress updates, in the order they were first seen
+    files: Vec<String>,
+}
+
+/// A video of a job and the file it ended up in
+#[derive(Debug, Clone, Default)]
+struct ReportItem {
+    video: PrintedVideo,
+    // Set once YT-DLP moved the finished file into place
+    file_path: Option<String>,
+}
+
+enum JobOutcome {
+    Finished(DownloadResult),
+    Failed(AppError),
+    Cancelled,
+    Paused,
+}
+
+/// Why a running job's YT-DLP process is being stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StopReason {
+    Cancel,
+    Pause,
+}
+
+/// How a YT-DLP run ended when it didn't fail
+enum RunEnd {
+    Completed(DownloadResult),
+    Stopped(StopReason),
+}
+
+struct JobEntry {
+    job: DownloadJob,
+    stop_sender: Option<oneshot::Sender<StopReason>>,
+    window: tauri::Window,
+}
+
+// All jobs known to this session, in the order they were enqueued
+static JOBS: Mutex<Vec<JobEntry>> = Mutex::new(Vec::new());
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+// Unfinished jobs from the previous session awaiting a resume or discard decision.
+// None until loaded from the queue file.
+static INTERRUPTED_JOBS: Mutex<Option<Vec<DownloadJob>>> = Mutex::new(None);
+
+fn lock_jobs() -> Result<std::sync::MutexGuard<'static, Vec<JobEntry>>, String> {
+    JOBS.lock().map_err(|e| format!("Lock error: {}", e))
+}
+
+/// Lock the interrupted jobs, loading them from the queue file on first use.
+/// Always lock JOBS first when both are needed.
+fn lock_interrupted_jobs() -> Result<std::sync::MutexGuard<'static, Option<Vec<DownloadJob>>>, String> {
+    let mut interrupted = INTERRUPTED_JOBS.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    if interrupted.is_none() {
+        let mut jobs = queue::load_jobs().unwrap_or_else(|_e| {
+            #[cfg(debug_assertions)]
+            eprintln!("[DEBUG] Failed to load saved queue: {}", _e);
+            Vec::new()
+        });
+        for job in jobs.iter_mut() {
+            if job.state != JobState::Paused {
+                job.state = JobState::Paused;
+                job.message = Some("Interrupted when the app closed".to_string());
+            }
+        }
+
+        // Interrupted jobs keep their IDs, so this session's jobs are numbered after them
+        let next_id = jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+        NEXT_JOB_ID.fetch_max(next_id, Ordering::SeqCst);
+        *interrupted = Some(jobs);
+    }
+
+    Ok(interrupted)
+}
+
+/// Save this session's unfinished jobs, and those still waiting from the previous session,
+/// so they can be offered for resumption after a restart
+fn save_queue(jobs: &[JobEntry]) {
+    let mut unfinished: Vec<DownloadJob> = jobs
+        .iter()
+        .filter(|entry| matches!(entry.job.state, JobState::Queued | JobState::Running | JobState::Paused))
+        .map(|entry| entry.job.clone())
+        .collect();
+
+    if let Ok(interrupted) = lock_interrupted_jobs() {
+        unfinished.extend(interrupted.iter().flatten().cloned());
+    }
+
+    if let Err(_e) = queue::save_jobs(&unfinished) {
+        #[cfg(debug_assertions)]
+        eprintln!("[DEBUG] Failed to save download queue: {}", _e);
+    }
+}
+
+/// A job ID not used by this session's jobs or the interrupted ones
+fn next_job_id() -> Result<JobId, String> {
+    // Loading the interrupted jobs moves NEXT_JOB_ID past their IDs
+    drop(lock_interrupted_jobs()?);
+    Ok(NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst))
+}
+
+fn emit_job_update(window: &tauri::Window, job: &DownloadJob) {
+    let _ = window.emit("download-job", job.clone());
+}
+
+/// Add a download to the queue and return its job ID
+pub fn enqueue(
+    url: String,
+    quality: Option<String>,
+    mut options: DownloadOptions,
+    window: tauri::Window,
+) -> Result<JobId, String> {
+    options.validate()?;
+    let download_dir = config::get_download_path()?.to_string_lossy().to_string();
+    // Keep the template the job was queued with, even if the setting changes meanwhile
+    if options.output_template.is_none() {
+        options.output_template = Some(config::load_config().output_template);
+    }
+    add_job(url, quality, options, download_dir, Vec::new(), window)
+}
+
+fn add_job(
+    url: String,
+    quality: Option<String>,
+    options: DownloadOptions,
+    download_dir: String,
+    // Files of an interrupted run this job continues, so they are cleaned up and kept from scans
+    files: Vec<String>,
+    window: tauri::Window,
+) -> Result<JobId, String> {
+    let id = next_job_id()?;
+    let job = DownloadJob {
+        id,
+        url,
+        quality,
+        options,
+        download_dir: Some(download_dir),
+        state: JobState::Queued,
+        message: None,
+        error: None,
+        result: None,
+        attempts: Vec::new(),
+        files,
+    };
+
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] Enqueued download job {} for url: {}", id, job.url);
+
+    emit_job_update(&window, &job);
+    {
+        let mut jobs = lock_jobs()?;
+        jobs.push(JobEntry {
+            job,
+            stop_sender: None,
+            window,
+        });
+        save_queue(&jobs);
+    }
+
+    start_queued_jobs()?;
+    Ok(id)
+}
+
+pub fn list() -> Result<Vec<DownloadJob>, String> {
+    Ok(lock_jobs()?.iter().map(|entry| entry.job.clone()).collect())
+}
+
+/// Jobs the previous session didn't finish, offered for resumption
+pub fn list_interrupted() -> Result<Vec<DownloadJob>, String> {
+    Ok(lock_interrupted_jobs()?.clone().unwrap_or_default())
+}
+
+/// Queue interrupted jobs again under new job IDs. They download to the same folder
+/// with --continue, so existing partial files are reused.
+pub fn resume_interrupted(ids: &[JobId], window: tauri::Window) -> Result<Vec<JobId>, String> {
+    let resumed: Vec<DownloadJob> = {
+        let mut interrupted = lock_interrupted_jobs()?;
+        let jobs = interrupted.get_or_insert_with(Vec::new);
+        let (resumed, remaining) = jobs.drain(..).partition(|job| ids.contains(&job.id));
+        *jobs = remaining;
+        resumed
+    };
+
+    let mut new_ids = Vec::new();
+    for job in resumed {
+        let download_dir = match job.download_dir {
+            Some(dir) => dir,
+            None => config::get_download_path()?.to_string_lossy().to_string(),
+        };
+        new_ids.push(add_job(job.url, job.quality, job.options, download_dir, job.files, window.clone())?);
+    }
+
+    Ok(new_ids)
+}
+
+/// Forget interrupted jobs without resuming them. Their partial files are handled like a cancellation's.
+pub fn discard_interrupted(ids: &[JobId]) -> Result<(), String> {
+    let jobs = lock_jobs()?;
+    if let Some(interrupted) = lock_interrupted_jobs()?.as_mut() {
+        let (discarded, remaining): (Vec<DownloadJob>, Vec<DownloadJob>) =
+            interrupted.drain(..).partition(|job| ids.contains(&job.id));
+        *interrupted = remaining;
+
+        for job in &discarded {
+            clean_up_files(job, JobState::Cancelled);
+        }
+    }
+    save_queue(&jobs);
+    Ok(())
+}
+
+/// Files of downloads that may still continue, which a leftover scan must not report
+pub fn active_files() -> Result<Vec<String>, String> {
+    let jobs = lock_jobs()?;
+    let mut files: Vec<String> = jobs
+        .iter()
+        .filter(|entry| matches!(entry.job.state, JobState::Queued | JobState::Running | JobState::Paused))
+        .flat_map(|entry| entry.job.files.clone())
+        .collect();
+
+    files.extend(lock_interrupted_jobs()?.iter().flatten().flat_map(|job| job.files.clone()));
+    Ok(files)
+}
+
+/// Final paths of the files finished jobs produced
+pub fn downloaded_files() -> Result<Vec<String>, String> {
+    Ok(lock_jobs()?
+        .iter()
+        .filter_map(|entry| entry.job.result.as_ref())
+        .flat_map(|result| result.file_paths.clone())
+        .collect())
+}
+
+/// Delete a stopped job's partial files if the cleanup policy asks for it
+fn clean_up_files(job: &DownloadJob, state: JobState) {
+    let policy = config::load_config().cleanup;
+    let delete = match state {
+        JobState::Cancelled => policy.delete_on_cancel,
+        JobState::Failed => policy.delete_on_failure,
+        _ => false,
+    };
+
+    if delete {
+        cleanup::delete_partial_files(&job.files);
+    }
+}
+
+pub fn cancel(job_id: JobId) -> Result<(), String> {
+    let mut jobs = lock_jobs()?;
+    let entry = jobs
+        .iter_mut()
+        .find(|entry| entry.job.id == job_id)
+        .ok_or_else(|| format!("No download with ID {}", job_id))?;
+
+    match entry.job.state {
+        JobState::Queued | JobState::Paused => {
+            // No process to stop. A paused job already ran, so its cancellation is recorded
+            // like that of a running one; pausing itself left no history entry.
+            if entry.job.state == JobState::Paused {
+                let started_at = entry
+                    .job
+                    .attempts
+                    .first()
+                    .map_or_else(unix_timestamp, |attempt| attempt.started_at);
+                record_history(&entry.job, &JobOutcome::Cancelled, &JobReport::default(), started_at);
+            }
+
+            entry.job.state = JobState::Cancelled;
+            emit_job_update(&entry.window, &entry.job);
+            clean_up_files(&entry.job, JobState::Cancelled);
+            save_queue(&jobs);
+        }
+        JobState::Running => {
+            // The job task observes the signal, kills YT-DLP and records the cancellation
+            if let Some(sender) = entry.stop_sender.take() {
+                let _ = sender.send(StopReason::Cancel);
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Stop a job while keeping its partial files, so it can be resumed later
+pub fn pause(job_id: JobId) -> Result<(), String> {
+    let mut jobs = lock_jobs()?;
+    let entry = jobs
+        .iter_mut()
+        .find(|entry| entry.job.id == job_id)
+        .ok_or_else(|| format!("No download with ID {}", job_id))?;
+
+    match entry.job.state {
+        JobState::Queued => {
+            // Keep it from starting until resumed
+            entry.job.state = JobState::Paused;
+            emit_job_update(&entry.window, &entry.job);
+            save_queue(&jobs);
+        }
+        JobState::Running => {
+            // The job task kills YT-DLP and marks the job paused
+            if let Some(sender) = entry.stop_sender.take() {
+                let _ = sender.send(StopReason::Pause);
+            }
+        }
+        _ => return Err(format!("Download {} can't be paused", job_id)),
+    }
+
+    Ok(())
+}
+
+/// Queue a paused job again. YT-DLP continues from the partial files it left behind.
+pub fn resume(job_id: JobId) -> Result<(), String> {
+    {
+        let mut jobs = lock_jobs()?;
+        let entry = jobs
+            .iter_mut()
+            .find(|entry| entry.job.id == job_id)
+            .ok_or_else(|| format!("No download with ID {}", job_id))?;
+
+        if entry.job.state != JobState::Paused {
+            return Err(format!("Download {} is not paused", job_id));
+        }
+
+        entry.job.state = JobState::Queued;
+        entry.job.message = None;
+        emit_job_update(&entry.window, &entry.job);
+        save_queue(&jobs);
+    }
+
+    start_queued_jobs()
+}
+
+/// Start queued jobs in FIFO order until the concurrency limit is reached.
+/// The limit is re-read from the config each time, so changes apply as soon as a slot frees up.
+pub fn start_queued_jobs() -> Result<(), String> {
+    let max_concurrent = config::load_config().max_concurrent_downloads.max(1);
+    let mut jobs = lock_jobs()?;
+
+    let mut running = jobs
+        .iter()
+        .filter(|entry| entry.job.state == JobState::Running)
+        .count();
+
+    for entry in jobs.iter_mut().filter(|entry| entry.job.state == JobState::Queued) {
+        if running >= max_concurrent {
+            break;
+        }
+        running += 1;
+
+        let (stop_tx, stop_rx) = oneshot::channel::<StopReason>();
+        entry.stop_sender = Some(stop_tx);
+        entry.job.state = JobState::Running;
+        emit_job_update(&entry.window, &entry.job);
+
+        #[cfg(debug_assertions)]
+        eprintln!("[DEBUG] Starting download job {} ({}/{} running)", entry.job.id, running, max_concurrent);
+
+        let job = entry.job.clone();
+        let window = entry.window.clone();
+        tokio::spawn(async move {
+            let started_at = unix_timestamp();
+            let (outcome, report) = run_job(&job, &window, stop_rx).await;
+            // A paused job isn't done yet; it's recorded once it finishes after resuming
+            if !matches!(outcome, JobOutcome::Paused) {
+                record_history(&job, &outcome, &report, started_at);
+            }
+            finish_job(job.id, outcome);
+        });
+    }
+
+    Ok(())
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Record one history entry per video of the job. Videos whose file was moved into place count
+/// as finished; the rest, or the job itself when no video started, get the job's outcome.
+fn record_history(job: &DownloadJob, outcome: &JobOutcome, report: &JobReport, started_at: u64) {
+    let (outcome, error) = match outcome {
+        JobOutcome::Finished(_) => (HistoryOutcome::Finished, None),
+        JobOutcome::Failed(error) => (HistoryOutcome::Failed, Some(error.clone())),
+        JobOutcome::Cancelled | JobOutcome::Paused => (HistoryOutcome::Cancelled, None),
+    };
+    let error_kind = error.as_ref().map(|error| error.kind);
+    let error = error.map(|error| error.message);
+
+    let no_items = [ReportItem::default()];
+    let items = if report.items.is_empty() { &no_items[..] } else { &report.items[..] };
+
+    for item in items {
+        let video = item.video.clone();
+        let filesize = item
+            .file_path
+            .as_ref()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len());
+        let finished = item.file_path.is_some();
+
+        let entry = HistoryEntry {
+            id: 0, // Assigned by the history store
+            // Playlist items link to their own video rather than the playlist
+            url: match video.webpage_url {
+                Some(url) if items.len() > 1 => url,
+                _ => job.url.clone(),
+            },
+            extractor: video.extractor,
+            video_id: video.id,
+            title: video.title,
+            format: video.format.or(video.format_id),
+            file_path: item.file_path.clone(),
+            filesize,
+            started_at,
+            finished_at: unix_timestamp(),
+            outcome: if finished { HistoryOutcome::Finished } else { outcome },
+            error: if finished { None } else { error.clone() },
+            error_kind: if finished { None } else { error_kind },
+        };
+
+        if let Err(_e) = history::add_entry(entry) {
+            #[cfg(debug_assertions)]
+            eprintln!("[DEBUG] Failed to record download history: {}", _e);
+        }
+    }
+}
+
+fn finish_job(job_id: JobId, outcome: JobOutcome) {
+    if let Ok(mut jobs) = lock_jobs() {
+        if let Some(entry) = jobs.iter_mut().find(|entry| entry.job.id == job_id) {
+            entry.stop_sender = None;
+            let (state, message, error, result) = match outcome {
+                JobOutcome::Finished(result) => {
+                    // Name the file when there is exactly one, otherwise the folder they went to
+                    let location = match result.file_paths.as_slice() {
+                        [path] => path.clone(),
+                        _ => result.download_dir.clone(),
+                    };
+                    let message = format!("Download completed to: {}", location);
+                    (JobState::Finished, Some(message), None, Some(result))
+                }
+                JobOutcome::Failed(error) => (JobState::Failed, Some(error.message.clone()), Some(error), None),
+                JobOutcome::Cancelled => (JobState::Cancelled, None, None, None),
+                JobOutcome::Paused => (JobState::Paused, Some("Download paused".to_string()), None, None),
+            };
+            entry.job.state = state;
+            entry.job.message = message;
+            entry.job.error = error;
+            entry.job.result = result;
+            emit_job_update(&entry.window, &entry.job);
+            clean_up_files(&entry.job, state);
+        }
+        save_queue(&jobs);
+    }
+
+    let _ = start_queued_jobs();
+}
+
+/// Add a finished attempt to a job, optionally updating its message, and notify the frontend
+fn record_attempt(job_id: JobId, attempt: JobAttempt, message: Option<String>, files: &[String]) {
+    if let Ok(mut jobs) = lock_jobs() {
+        if let Some(entry) = jobs.iter_mut().find(|entry| entry.job.id == job_id) {
+            entry.job.attempts.push(attempt);
+            for file in files {
+                if !entry.job.files.contains(file) {
+                    entry.job.files.push(file.clone());
+                }
+            }
+            if message.is_some() {
+                entry.job.message = message;
+            }
+            emit_job_update(&entry.window, &entry.job);
+        }
+    }
+}
+
+async fn run_job(job: &DownloadJob, window: &tauri::Window, mut stop_rx: oneshot::Receiver<StopReason>) -> (JobOutcome, JobReport) {
+    let retry = config::load_config().retry;
+
+    // Hand the cached probe result to YT-DLP so it doesn't extract the URL again
+    let info_json = if job.options.reuse_probe {
+        probe::write_info_json(&job.url, &format!("job-{}", job.id)).unwrap_or_else(|_e| {
+            #[cfg(debug_assertions)]
+            eprintln!("[DEBUG] Not reusing probe result: {}", _e);
+            None
+        })
+    } else {
+        None
+    };
+
+    let mut attempt = 1;
+    let (outcome, report) = loop {
+        let mut report = JobReport::default();
+        let started_at = unix_timestamp();
+
+        let outcome = match run_ytdlp(job, info_json.as_deref(), window, &mut stop_rx, &mut report).await {
+            Ok(RunEnd::Completed(result)) => JobOutcome::Finished(result),
+            Ok(RunEnd::Stopped(reason)) => stop_outcome(reason),
+            Err(error) => JobOutcome::Failed(error),
+        };
+
+        let error = match &outcome {
+            JobOutcome::Failed(error) => Some(error.clone()),
+            _ => None,
+        };
+        let retry_delay = match &error {
+            Some(error) if error.is_transient() && attempt < retry.max_attempts => Some(retry.delay(attempt)),
+            _ => None,
+        };
+
+        let message = retry_delay.map(|delay| {
+            format!(
+                "Download interrupted, retrying in {}s (attempt {} of {})",
+                delay.as_secs(),
+                attempt + 1,
+                retry.max_attempts
+            )
+        });
+        record_attempt(job.id, JobAttempt {
+            attempt,
+            started_at,
+            finished_at: unix_timestamp(),
+            error,
+            output: report.output.clone(),
+        }, message, &report.files);
+
+        let Some(delay) = retry_delay else {
+            break (outcome, report);
+        };
+
+        #[cfg(debug_assertions)]
+        eprintln!("[DEBUG] Job {} failed transiently, retrying in {:?}", job.id, delay);
+
+        // Wait out the backoff, unless the job is cancelled or paused meanwhile
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            reason = &mut stop_rx => break (stop_outcome(reason.unwrap_or(StopReason::Cancel)), report),
+        }
+        attempt += 1;
+    };
+
+    if let Some(path) = info_json {
+        let _ = std::fs::remove_file(path);
+    }
+
+    (outcome, report)
+}
+
+/// Forward a line of YT-DLP output to the frontend, as a typed progress event when it is one,
+/// and record what it tells us about the download
+fn handle_output_line(window: &tauri::Window, job_id: JobId, line: &str, report: &mut JobReport) {
+    match ytdlp_output::parse_line(job_id, line) {
+        OutputLine::Progress(progress) => {
+            if let Some(filename) = &progress.filename {
+                track_file(job_id, filename, report);
+            }
+            let _ = window.emit("download-progress", progress);
+        }
+        OutputLine::Video(video) => {
+            report.items.push(ReportItem { video, file_path: None });
+        }
+        OutputLine::FilePath(path) => {
+            match report.items.last_mut() {
+                Some(item) if item.file_path.is_none() => item.file_path = Some(path.clone()),
+                _ => report.items.push(ReportItem {
+                    video: PrintedVideo::default(),
+                    file_path: Some(path.clone()),
+                }),
+            }
+            report.file_paths.push(path);
+        }
+        OutputLine::Console => {
+            if let Some(path) = ytdlp_output::parse_destination(line) {
+                track_file(job_id, &path, report);
+            }
+            if line.starts_with("ERROR:") {
+                report.errors.push(line.to_string());
+            }
+            if report.output.len() >= MAX_ATTEMPT_OUTPUT_LINES {
+                report.output.remove(0);
+            }
+            report.output.push(line.to_string());
+            let _ = window.emit("download-output", DownloadOutput {
+                job_id,
+                line: line.to_string(),
+            });
+        }
+    }
+}
+
+/// Remember a file the job writes as soon as YT-DLP names it, and save the queue,
+/// so the file is known to cleanup and leftover scans even if the app crashes mid-download
+fn track_file(job_id: JobId, file: &str, report: &mut JobReport) {
+    if report.files.iter().any(|known| known == file) {
+        return;
+    }
+    report.files.push(file.to_string());
+
+    if let Ok(mut jobs) = lock_jobs() {
+        if let Some(entry) = jobs.iter_mut().find(|entry| entry.job.id == job_id) {
+            if !entry.job.files.iter().any(|known| known == file) {
+                entry.job.files.push(file.to_string());
+                save_queue(&jobs);
+            }
+        }
+    }
+}
+
+fn stop_outcome(reason: StopReason) -> JobOutcome {
+    match reason {
+        StopReason::Cancel => JobOutcome::Cancelled,
+        StopReason::Pause => JobOutcome::Paused,
+    }
+}
+
+/// Run YT-DLP for a job, filling in `report` from its output
+async fn run_ytdlp(
+    job: &DownloadJob,
+    info_json: Option<&Path>,
+    window: &tauri::Window,
+    stop_rx: &mut oneshot::Receiver<StopReason>,
+    report: &mut JobReport,
+) -> Result<RunEnd, AppError> {
+    let job_id = job.id;
+
+    let mut cmd = ytdlp_manager::ytdlp_command()
+        .await
+        .map_err(|e| {
+            #[cfg(debug_assertions)]
+            eprintln!("[DEBUG] {}", e);
+            e
+        })?;
+
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] YT-DLP command: {:?}", cmd);
+
+    let download_dir = job
+        .download_dir
+        .as_ref()
+        .map(|dir| Ok(std::path::PathBuf::from(dir)))
+        .unwrap_or_else(config::get_download_path)
+        .map_err(|e| {
+            #[cfg(debug_assertions)]
+            eprintln!("[DEBUG] Failed to get download path: {}", e);
+            e
+        })?;
+
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] Download directory: {:?}", download_dir);
+
+    let template = job
+        .options
+        .output_template
+        .clone()
+        .unwrap_or_else(|| config::load_config().output_template);
+
+    cmd.arg("--output")
+        .arg(format!("{}/{}", download_dir.to_string_lossy(), template.trim()))
+        .arg("--newline")
+        .arg("--progress")
+        .arg("--no-warnings")
+        .arg("--continue")  // Resume .part files left by an earlier attempt
+        .arg("--color")  // Progress is parsed from the template output,
+        .arg("never")    // so keep escape codes out of it
+        .args(ytdlp_output::progress_template_args())
+        .args(ytdlp_output::print_args())
+        .args(download_options::format_args(job.quality.as_deref(), &job.options))
+        .args(download_options::subtitle_args(&job.options))
+        .args(download_options::embedding_args(&job.options))
+        .args(download_options::playlist_args(&job.options));
+
+    match info_json {
+        Some(path) => cmd.arg("--load-info-json").arg(path),
+        None => cmd.arg(&job.url),
+    };
+
+    cmd.stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        // Stop YT-DLP with the app, or it keeps writing the .part file the next launch offers to resume
+        .kill_on_drop(true);
+
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] Executing YT-DLP command for job {}...", job_id);
+
+    let mut child = cmd.spawn()
+        .map_err(|e| {
+            #[cfg(debug_assertions)]
+            eprintln!("[DEBUG] Failed to spawn YT-DLP process: {}", e);
+            AppError::spawn_failed(e)
+        })?;
+
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] YT-DLP process spawned successfully");
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    let mut stdout_reader = BufReader::new(stdout);
+    let mut stderr_reader = BufReader::new(stderr);
+
+    // Capture console output from YT-DLP
+    // Create a cancellation channel for the progress task
+    let (progress_cancel_tx, mut progress_cancel_rx) = oneshot::channel::<()>();
+
+    let window_clone = window.clone();
+    let progress_task = tokio::spawn(async move {
+        let mut report = JobReport::default();
+        let mut stdout_buf = Vec::new();
+        let mut stderr_buf = Vec::new();
+        // Keep reading until both streams end so the last printed lines (e.g. file paths) aren't lost
+        let mut stdout_done = false;
+        let mut stderr_done = false;
+
+        while !(stdout_done && stderr_done) {
+            tokio::select! {
+                _ = &mut progress_cancel_rx => {
+                    // Cancellation requested - stop processing output
+                    break;
+                }
+                result = stdout_reader.read_until(b'\n', &mut stdout_buf), if !stdout_done => {
+                    match result {
+                        Ok(0) => {
+                            // EOF
+                            #[cfg(debug_assertions)]
+                            eprintln!("[DEBUG] stdout stream ended");
+                            stdout_done = true;
+                        }
+                        Ok(_) => {
+                            // Convert bytes to string using lossy UTF-8 conversion
+                            // This handles progress indicators and special characters gracefully
+                            let line = String::from_utf8_lossy(&stdout_buf);
+                            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+
+                            if !line.is_empty() {
+                                #[cfg(debug_assertions)]
+                                eprintln!("[DEBUG] YT-DLP stdout: {}", line);
+                                handle_output_line(&window_clone, job_id, line, &mut report);
+                            }
+
+                            stdout_buf.clear();
+                        }
+                        Err(e) => {
+                            #[cfg(debug_assertions)]
+                            eprintln!("[DEBUG] stdout read error: {:?}", e);
+                            // Continue reading on errors - don't break the download
+                            if e.kind() != std::io::ErrorKind::Interrupted {
+                                stdout_done = true;
+                            }
+                        }
+                    }
+                }
+                result = stderr_reader.read_until(b'\n', &mut stderr_buf), if !stderr_done => {
+                    match result {
+                        Ok(0) => {
+                            // EOF
+                            #[cfg(debug_assertions)]
+                            eprintln!("[DEBUG] stderr stream ended");
+                            stderr_done = true;
+                        }
+                        Ok(_) => {
+                            // Convert bytes to string using lossy UTF-8 conversion
+                            // This handles progress indicators and special characters gracefully
+                            let line = String::from_utf8_lossy(&stderr_buf);
+                            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+
+                            if !line.is_empty() {
+                                #[cfg(debug_assertions)]
+                                eprintln!("[DEBUG] YT-DLP stderr: {}", line);
+                                // YT-DLP often uses stderr for progress
+                                handle_output_line(&window_clone, job_id, line, &mut report);
+                            }
+
+                            stderr_buf.clear();
+                        }
+                        Err(e) => {
+                            #[cfg(debug_assertions)]
+                            eprintln!("[DEBUG] stderr read error: {:?}", e);
+                            // Continue reading on errors - don't break the download
+                            if e.kind() != std::io::ErrorKind::Interrupted {
+                                stderr_done = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        report
+    });
+
+    // Wait for process, cancellation or pause
+    let status = tokio::select! {
+        result = child.wait() => {
+            result.map_err(|e| format!("Failed to wait for process: {}", e))?
+        }
+        reason = stop_rx => {
+            // Cancel or pause requested - kill the process. YT-DLP leaves its .part files,
+            // which --continue picks up when a paused job is resumed.
+            let _ = child.kill().await;
+            let _ = child.wait().await;
+            // Cancel progress task
+            let _ = progress_cancel_tx.send(());
+            *report = progress_task.await.unwrap_or_default();
+            return Ok(RunEnd::Stopped(reason.unwrap_or(StopReason::Cancel)));
+        }
+    };
+
+    // The process has exited, so the progress task finishes once it has drained the pipes
+    *report = progress_task.await.unwrap_or_default();
+
+    if !status.success() {
+        return Err(AppError::from_ytdlp(status.code(), &report.errors.join("\n")));
+    }
+
+    // Emit completion message
+    let _ = window.emit("download-output", DownloadOutput {
+        job_id,
+        line: "Download completed successfully".to_string(),
+    });
+
+    Ok(RunEnd::Completed(DownloadResult {
+        download_dir: download_dir.to_string_lossy().to_string(),
+        file_paths: report.file_paths.clone(),
+    }))
+}
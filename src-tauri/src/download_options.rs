@@ -0,0 +1,415 @@
+use crate::output_template;
+use crate::playlist;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+// 0 (best) to 10 (worst), or a bitrate such as 192K
+static AUDIO_QUALITY: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^(10|[0-9]|[1-9][0-9]*[kK])$").unwrap());
+
+/// Per-download options beyond the quality selection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadOptions {
+    // Extract audio only instead of downloading video
+    pub audio: Option<AudioOptions>,
+    // Output container for video downloads. None keeps the QuickTime-compatible MP4 default.
+    pub container: Option<Container>,
+    pub subtitles: Option<SubtitleOptions>,
+    // Embed the thumbnail as cover art
+    pub embed_thumbnail: bool,
+    // Write title, uploader, upload date, description and URL tags
+    pub embed_metadata: bool,
+    // Add chapter markers
+    pub embed_chapters: bool,
+    // Sidecar files written next to the download
+    pub write_info_json: bool,
+    pub write_description: bool,
+    pub write_thumbnail: bool,
+    // For playlist URLs, the entries to download, e.g. "1,3,5-7" or "1:" for all of them.
+    // When unset only the video itself is downloaded, e.g. for a watch URL with a list parameter.
+    pub playlist_items: Option<String>,
+    // Download from the cached probe result instead of extracting the URL again
+    pub reuse_probe: bool,
+    // Output template for this download instead of the configured one
+    pub output_template: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleOptions {
+    // Language codes as listed by the probe, e.g. ["en", "de"]
+    pub languages: Vec<String>,
+    pub format: SubtitleFormat,
+    // Embed into the media file instead of writing sidecar files
+    pub embed: bool,
+    // Fall back to auto-generated captions for languages without manual subtitles
+    #[serde(default)]
+    pub include_automatic: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+    Ass,
+}
+
+impl SubtitleFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+            SubtitleFormat::Ass => "ass",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Container {
+    Mp4,
+    Mkv,
+    Webm,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioOptions {
+    pub codec: AudioCodec,
+    // Either a VBR quality from 0 (best) to 10 (worst) or a bitrate such as "192K"
+    pub quality: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioCodec {
+    Mp3,
+    M4a,
+    Opus,
+    Flac,
+    Wav,
+}
+
+impl AudioCodec {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AudioCodec::Mp3 => "mp3",
+            AudioCodec::M4a => "m4a",
+            AudioCodec::Opus => "opus",
+            AudioCodec::Flac => "flac",
+            AudioCodec::Wav => "wav",
+        }
+    }
+}
+
+impl DownloadOptions {
+    /// Reject options YT-DLP would fail on, before the job is queued
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(quality) = self.audio.as_ref().and_then(|audio| audio.quality.as_ref()) {
+            if !AUDIO_QUALITY.is_match(quality.trim()) {
+                return Err(format!(
+                    "Invalid audio quality '{}': use 0 (best) to 10 (worst) or a bitrate such as 192K",
+                    quality
+                ));
+            }
+        }
+
+        if let Some(template) = &self.output_template {
+            output_template::validate(template)?;
+        }
+
+        if let Some(items) = &self.playlist_items {
+            playlist::validate_items(items)?;
+        }
+
+        if self.embed_thumbnail {
+            let unsupported = match &self.audio {
+                Some(audio) => audio.codec == AudioCodec::Wav,
+                None => self.container == Some(Container::Webm),
+            };
+            if unsupported {
+                return Err("Thumbnails can't be embedded in WebM or WAV files".to_string());
+            }
+        }
+
+        if let Some(subtitles) = &self.subtitles {
+            if subtitles.languages.iter().all(|language| language.trim().is_empty()) {
+                return Err("Select at least one subtitle language".to_string());
+            }
+            if subtitles.embed && self.audio.is_some() {
+                return Err("Subtitles can only be embedded in video downloads".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Subtitle download, conversion and embedding arguments
+pub fn subtitle_args(options: &DownloadOptions) -> Vec<String> {
+    let Some(subtitles) = &options.subtitles else {
+        return Vec::new();
+    };
+
+    let languages: Vec<&str> = subtitles
+        .languages
+        .iter()
+        .map(|language| language.trim())
+        .filter(|language| !language.is_empty())
+        .collect();
+    let format = subtitles.format.as_str();
+
+    let mut args = vec![
+        "--write-subs".to_string(),
+        "--sub-langs".to_string(),
+        languages.join(","),
+        // Download the requested format when offered, otherwise convert to it
+        "--sub-format".to_string(),
+        format!("{}/best", format),
+        "--convert-subs".to_string(),
+        format.to_string(),
+    ];
+
+    if subtitles.include_automatic {
+        args.push("--write-auto-subs".to_string());
+    }
+
+    if subtitles.embed {
+        args.push("--embed-subs".to_string());
+        // Delete the subtitle files once embedded, even though --write-subs was given
+        args.push("--compat-options".to_string());
+        args.push("no-keep-subs".to_string());
+    }
+
+    args
+}
+
+/// Playlist entry selection arguments. Without a selection the playlist is never expanded, as the
+/// probe result that would pin the download to a single video may have expired by the time it runs.
+pub fn playlist_args(options: &DownloadOptions) -> Vec<String> {
+    match &options.playlist_items {
+        Some(items) => vec![
+            "--yes-playlist".to_string(),
+            "--playlist-items".to_string(),
+            items.replace(' ', ""),
+        ],
+        None => vec!["--no-playlist".to_string()],
+    }
+}
+
+/// Thumbnail, metadata and chapter embedding plus sidecar file arguments
+pub fn embedding_args(options: &DownloadOptions) -> Vec<String> {
+    let flags = [
+        (options.embed_thumbnail, "--embed-thumbnail"),
+        // Tags title, uploader (artist), upload date, description and webpage URL
+        (options.embed_metadata, "--embed-metadata"),
+        (options.embed_chapters, "--embed-chapters"),
+        (options.write_info_json, "--write-info-json"),
+        (options.write_description, "--write-description"),
+        (options.write_thumbnail, "--write-thumbnail"),
+    ];
+
+    flags
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, flag)| flag.to_string())
+        .collect()
+}
+
+/// Format selection and post-processing arguments for a download
+pub fn format_args(quality: Option<&str>, options: &DownloadOptions) -> Vec<String> {
+    let mut args = Vec::new();
+
+    if let Some(audio) = &options.audio {
+        // Pick an audio stream and let YT-DLP convert it to the requested codec
+        let format_selector = match quality {
+            None | Some("best") => "bestaudio/best".to_string(),
+            Some("worst") => "worstaudio/worst".to_string(),
+            Some(format_id) => format!("{}/bestaudio/best", format_id),
+        };
+        args.push("-f".to_string());
+        args.push(format_selector);
+        args.push("--extract-audio".to_string());
+        args.push("--audio-format".to_string());
+        args.push(audio.codec.as_str().to_string());
+        if let Some(audio_quality) = &audio.quality {
+            args.push("--audio-quality".to_string());
+            args.push(audio_quality.trim().to_string());
+        }
+        return args;
+    }
+
+    match options.container {
+        None | Some(Container::Mp4) => args.extend(mp4_format_args(quality)),
+        Some(Container::Mkv) => {
+            // MKV holds any codec, so take the best streams and let FFmpeg merge them
+            let format_selector = match quality {
+                Some("worst") => "worstvideo+worstaudio/worst".to_string(),
+                None | Some("best") => "bestvideo+bestaudio/best".to_string(),
+                Some(format_id) => format!("{}+bestaudio/best", format_id),
+            };
+            args.push("--merge-output-format".to_string());
+            args.push("mkv".to_string());
+            args.push("-f".to_string());
+            args.push(format_selector);
+        }
+        Some(Container::Webm) => {
+            // WebM only holds VP8/VP9/AV1 video with Vorbis/Opus audio, so prefer WebM streams
+            let format_selector = match quality {
+                Some("worst") => "worstvideo[ext=webm]+worstaudio[ext=webm]/worst[ext=webm]/worst".to_string(),
+                None | Some("best") => "bestvideo[ext=webm]+bestaudio[ext=webm]/best[ext=webm]/bestvideo+bestaudio/best".to_string(),
+                Some(format_id) => format!("{}+bestaudio[ext=webm]/{}+bestaudio/best", format_id, format_id),
+            };
+            args.push("--merge-output-format".to_string());
+            args.push("webm".to_string());
+            args.push("-f".to_string());
+            args.push(format_selector);
+        }
+    }
+
+    args
+}
+
+/// QuickTime-compatible MP4 selection
+fn mp4_format_args(quality: Option<&str>) -> Vec<String> {
+    let mut args = Vec::new();
+
+    // Force MP4 output format for QuickTime compatibility
+    // YT-DLP will use native muxer for MP4 (no FFmpeg required)
+    args.push("--merge-output-format".to_string());
+    args.push("mp4".to_string());
+
+    // Add quality selector if specified
+    // Format selectors only use MP4 formats that can be merged natively without FFmpeg
+    // YT-DLP's native muxer can merge MP4 video + MP4 audio streams
+    let format_selector = match quality {
+        // For best/worst, prefer H.264+AAC MP4 for QuickTime compatibility
+        // Only select formats that are already MP4 (no re-encoding needed)
+        // Format selector: prefer H.264 video + AAC audio in MP4, fallback to any MP4
+        Some("worst") => "worstvideo[ext=mp4][vcodec^=avc1]+worstaudio[ext=mp4][acodec^=mp4a]/worstvideo[ext=mp4]+worstaudio[ext=mp4]/worst[ext=mp4]".to_string(),
+        // Default: prefer H.264+AAC MP4 for QuickTime compatibility
+        None | Some("best") => "bestvideo[ext=mp4][vcodec^=avc1]+bestaudio[ext=mp4][acodec^=mp4a]/bestvideo[ext=mp4]+bestaudio[ext=mp4]/best[ext=mp4]".to_string(),
+        // For specific format ID (resolution), pair it with best MP4 audio
+        // Only use MP4 formats to avoid needing FFmpeg
+        Some(format_id) => format!("{}+bestaudio[ext=mp4]/best[ext=mp4]", format_id),
+    };
+    args.push("-f".to_string());
+    args.push(format_selector);
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(codec: AudioCodec, quality: Option<&str>) -> DownloadOptions {
+        DownloadOptions {
+            audio: Some(AudioOptions {
+                codec,
+                quality: quality.map(str::to_string),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_options_are_valid() {
+        assert_eq!(DownloadOptions::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn accepts_vbr_and_bitrate_audio_quality() {
+        for quality in ["0", "5", "10", "192K", "320k", " 128K "] {
+            assert_eq!(audio(AudioCodec::Mp3, Some(quality)).validate(), Ok(()), "{}", quality);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_audio_quality() {
+        for quality in ["11", "-1", "K", "0K", "192 kbps", ""] {
+            assert!(audio(AudioCodec::Mp3, Some(quality)).validate().is_err(), "{}", quality);
+        }
+    }
+
+    fn subtitles(languages: &[&str], embed: bool) -> SubtitleOptions {
+        SubtitleOptions {
+            languages: languages.iter().map(|language| language.to_string()).collect(),
+            format: SubtitleFormat::Srt,
+            embed,
+            include_automatic: false,
+        }
+    }
+
+    #[test]
+    fn checks_subtitle_selection() {
+        let embedded = DownloadOptions {
+            subtitles: Some(subtitles(&["en"], true)),
+            ..Default::default()
+        };
+        assert_eq!(embedded.validate(), Ok(()));
+
+        let no_languages = DownloadOptions {
+            subtitles: Some(subtitles(&[" "], false)),
+            ..Default::default()
+        };
+        assert!(no_languages.validate().is_err());
+
+        let embedded_in_audio = DownloadOptions {
+            subtitles: Some(subtitles(&["en"], true)),
+            ..audio(AudioCodec::M4a, None)
+        };
+        assert!(embedded_in_audio.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_thumbnails_in_webm_and_wav() {
+        let webm = DownloadOptions {
+            container: Some(Container::Webm),
+            embed_thumbnail: true,
+            ..Default::default()
+        };
+        assert!(webm.validate().is_err());
+
+        let wav = DownloadOptions {
+            embed_thumbnail: true,
+            ..audio(AudioCodec::Wav, None)
+        };
+        assert!(wav.validate().is_err());
+
+        let mp3 = DownloadOptions {
+            embed_thumbnail: true,
+            ..audio(AudioCodec::Mp3, None)
+        };
+        assert_eq!(mp3.validate(), Ok(()));
+    }
+
+    #[test]
+    fn checks_output_template() {
+        let options = DownloadOptions {
+            output_template: Some("../%(title)s.%(ext)s".to_string()),
+            ..Default::default()
+        };
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn checks_playlist_items() {
+        let options = DownloadOptions {
+            playlist_items: Some("1,x".to_string()),
+            ..Default::default()
+        };
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn downloads_single_video_without_playlist_selection() {
+        assert_eq!(playlist_args(&DownloadOptions::default()), ["--no-playlist"]);
+
+        let options = DownloadOptions {
+            playlist_items: Some("1, 3-5".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(playlist_args(&options), ["--yes-playlist", "--playlist-items", "1,3-5"]);
+    }
+}
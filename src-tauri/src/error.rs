@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What went wrong, so the UI can suggest a fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    // YT-DLP could not be found or started
+    BinaryMissing,
+    Network,
+    GeoRestricted,
+    // Private, removed or otherwise unavailable video
+    Unavailable,
+    // Age-restricted or members-only content that needs cookies or a login
+    LoginRequired,
+    UnsupportedUrl,
+    FormatUnavailable,
+    DiskFull,
+    Cancelled,
+    FfmpegMissing,
+    // A playlist or channel URL where a single video is expected
+    Playlist,
+    Other,
+}
+
+/// Error returned by commands, serialized to the frontend as `{ kind, message }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppError {
+    pub kind: ErrorKind,
+    // Full error text, including YT-DLP's own output when there is any
+    pub message: String,
+}
+
+// Lowercase stderr fragments for each kind, checked in order.
+// More specific kinds come first, e.g. "video unavailable ... in your country" is geo-restricted.
+const YTDLP_ERROR_PATTERNS: &[(ErrorKind, &[&str])] = &[
+    (ErrorKind::FfmpegMissing, &[
+        "ffmpeg not found",
+        "ffprobe not found",
+        "ffprobe and ffmpeg not found",
+        "ffmpeg is not installed",
+    ]),
+    (ErrorKind::DiskFull, &[
+        "no space left on device",
+        "errno 28",
+        "not enough space on the disk",
+        "disk quota exceeded",
+    ]),
+    (ErrorKind::GeoRestricted, &[
+        "available in your country",
+        "geo restrict",
+        "geo-restrict",
+        "not available from your location",
+        "blocked it in your country",
+    ]),
+    (ErrorKind::LoginRequired, &[
+        "sign in to confirm your age",
+        "age-restricted",
+        "age restricted",
+        "inappropriate for some users",
+        "login required",
+        "requires authentication",
+        "members-only",
+        "join this channel",
+        "use --cookies",
+        "sign in to confirm you",
+    ]),
+    (ErrorKind::Unavailable, &[
+        "private video",
+        "this video is private",
+        "video unavailable",
+        "has been removed",
+        "account associated with this video has been terminated",
+        "no longer available",
+        "http error 404",
+    ]),
+    (ErrorKind::UnsupportedUrl, &[
+        "unsupported url",
+        "is not a valid url",
+    ]),
+    (ErrorKind::FormatUnavailable, &[
+        "requested format is not available",
+        "no video formats found",
+        "format not available",
+    ]),
+    (ErrorKind::Network, &[
+        "unable to download webpage",
+        "unable to download json metadata",
+        "urlopen error",
+        "connection reset",
+        "connection refused",
+        "connection aborted",
+        "timed out",
+        "name or service not known",
+        "temporary failure in name resolution",
+        "nodename nor servname provided",
+        "getaddrinfo failed",
+        "network is unreachable",
+        "http error 5",
+        "http error 429",
+        "ssl:",
+        "giving up after",
+        "incompleteread",
+        "incomplete read",
+        "content too short",
+        "did not get any data blocks",
+    ]),
+];
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        AppError {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Classify a failed YT-DLP run from its exit code and error output
+    pub fn from_ytdlp(exit_code: Option<i32>, error_output: &str) -> Self {
+        let error_output = error_output.trim();
+        let message = if error_output.is_empty() {
+            match exit_code {
+                Some(code) => format!("YT-DLP exited with code {}", code),
+                None => "YT-DLP was stopped".to_string(),
+            }
+        } else {
+            format!("YT-DLP error: {}", error_output)
+        };
+
+        let lower = error_output.to_lowercase();
+        let kind = YTDLP_ERROR_PATTERNS
+            .iter()
+            .find(|(_, patterns)| patterns.iter().any(|pattern| lower.contains(pattern)))
+            .map(|(kind, _)| *kind)
+            // Killed by a signal rather than exiting on its own
+            .unwrap_or(if exit_code.is_none() { ErrorKind::Cancelled } else { ErrorKind::Other });
+
+        AppError::new(kind, message)
+    }
+
+    /// Whether running YT-DLP again may succeed, e.g. after a network reset or throttling
+    pub fn is_transient(&self) -> bool {
+        self.kind == ErrorKind::Network
+    }
+
+    /// Failure to start YT-DLP at all
+    pub fn spawn_failed(error: std::io::Error) -> Self {
+        let kind = if error.kind() == std::io::ErrorKind::NotFound {
+            ErrorKind::BinaryMissing
+        } else {
+            ErrorKind::Other
+        };
+        AppError::new(kind, format!("Failed to execute YT-DLP: {}", error))
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::new(ErrorKind::Other, message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::new(ErrorKind::Other, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_ytdlp_errors() {
+        let cases = [
+            ("ERROR: [youtube] abc: Private video. Sign in if you've been granted access", ErrorKind::Unavailable),
+            ("ERROR: [youtube] abc: Video unavailable. The uploader has not made this video available in your country", ErrorKind::GeoRestricted),
+            ("ERROR: [youtube] abc: Sign in to confirm your age. This video may be inappropriate for some users.", ErrorKind::LoginRequired),
+            ("ERROR: Unsupported URL: https://example.com/", ErrorKind::UnsupportedUrl),
+            ("ERROR: [youtube] abc: Requested format is not available", ErrorKind::FormatUnavailable),
+            ("ERROR: unable to write data: [Errno 28] No space left on device", ErrorKind::DiskFull),
+            ("ERROR: Postprocessing: ffprobe and ffmpeg not found. Please install or provide the path", ErrorKind::FfmpegMissing),
+            ("ERROR: [youtube] abc: Unable to download webpage: <urlopen error [Errno -3] Temporary failure in name resolution>", ErrorKind::Network),
+            ("ERROR: unable to download video data: HTTP Error 429: Too Many Requests", ErrorKind::Network),
+            ("ERROR: Something unexpected happened", ErrorKind::Other),
+        ];
+
+        for (stderr, kind) in cases {
+            assert_eq!(AppError::from_ytdlp(Some(1), stderr).kind, kind, "{}", stderr);
+        }
+    }
+
+    #[test]
+    fn classification_ignores_case() {
+        let error = AppError::from_ytdlp(Some(1), "ERROR: CONNECTION RESET BY PEER");
+        assert_eq!(error.kind, ErrorKind::Network);
+    }
+
+    #[test]
+    fn message_includes_error_output() {
+        let error = AppError::from_ytdlp(Some(1), "  ERROR: Video unavailable\n");
+        assert_eq!(error.message, "YT-DLP error: ERROR: Video unavailable");
+    }
+
+    #[test]
+    fn empty_output_falls_back_to_exit_status() {
+        let error = AppError::from_ytdlp(Some(2), "");
+        assert_eq!(error.kind, ErrorKind::Other);
+        assert_eq!(error.message, "YT-DLP exited with code 2");
+
+        let error = AppError::from_ytdlp(None, "  ");
+        assert_eq!(error.kind, ErrorKind::Cancelled);
+        assert_eq!(error.message, "YT-DLP was stopped");
+    }
+
+    #[test]
+    fn only_network_errors_are_transient() {
+        assert!(AppError::new(ErrorKind::Network, "reset").is_transient());
+        assert!(AppError::from_ytdlp(Some(1), "ERROR: Read timed out.").is_transient());
+
+        for kind in [ErrorKind::Unavailable, ErrorKind::DiskFull, ErrorKind::Cancelled, ErrorKind::Other] {
+            assert!(!AppError::new(kind, "failed").is_transient());
+        }
+    }
+}
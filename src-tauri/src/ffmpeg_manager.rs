@@ -0,0 +1,148 @@
+use crate::error::{AppError, ErrorKind};
+use crate::ytdlp_manager;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct FfmpegLocation {
+    pub ffmpeg: PathBuf,
+    pub ffprobe: Option<PathBuf>,
+    pub source: String, // "path", "bundled" or "managed"
+}
+
+impl FfmpegLocation {
+    /// Value for YT-DLP's --ffmpeg-location. A directory lets YT-DLP find ffprobe too,
+    /// so use it whenever ffprobe sits next to ffmpeg.
+    pub fn ytdlp_location(&self) -> PathBuf {
+        let ffmpeg_dir = self.ffmpeg.parent();
+        match (&self.ffprobe, ffmpeg_dir) {
+            (Some(ffprobe), Some(dir)) if ffprobe.parent() == Some(dir) => dir.to_path_buf(),
+            _ => self.ffmpeg.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FfmpegVersionInfo {
+    pub version: String,
+    pub source: String,
+    pub ffmpeg_path: String,
+    pub ffprobe_path: Option<String>,
+}
+
+// Cache the FFmpeg location to avoid repeated lookups. Only found locations are cached,
+// so FFmpeg installed while the app runs is picked up by the next lookup.
+static FFMPEG_LOCATION_CACHE: Mutex<Option<FfmpegLocation>> = Mutex::new(None);
+
+pub async fn get_ffmpeg_location() -> Result<FfmpegLocation, String> {
+    let mut cache = FFMPEG_LOCATION_CACHE.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    // Look again if the cached binary was removed
+    if let Some(location) = cache.as_ref().filter(|location| location.ffmpeg.exists()) {
+        return Ok(location.clone());
+    }
+
+    let location = find_ffmpeg_location()?;
+    *cache = Some(location.clone());
+    Ok(location)
+}
+
+fn binary_name(name: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Directory where users can place their own FFmpeg build
+pub fn get_managed_ffmpeg_dir() -> Result<PathBuf, String> {
+    let managed_dir = dirs::data_dir()
+        .ok_or_else(|| "Failed to get data directory".to_string())?
+        .join("mac-ytdlp")
+        .join("ffmpeg");
+
+    std::fs::create_dir_all(&managed_dir)
+        .map_err(|e| format!("Failed to create FFmpeg directory: {}", e))?;
+
+    Ok(managed_dir)
+}
+
+/// Look for ffmpeg, and ffprobe beside it, in a directory
+fn find_in_dir(dir: &Path, source: &str) -> Option<FfmpegLocation> {
+    let ffmpeg = dir.join(binary_name("ffmpeg"));
+    if !ffmpeg.exists() {
+        return None;
+    }
+
+    let ffprobe = dir.join(binary_name("ffprobe"));
+    Some(FfmpegLocation {
+        ffmpeg,
+        ffprobe: ffprobe.exists().then_some(ffprobe),
+        source: source.to_string(),
+    })
+}
+
+fn find_ffmpeg_location() -> Result<FfmpegLocation, String> {
+    // First, check system PATH, same as for YT-DLP
+    if let Ok(ffmpeg) = which::which(binary_name("ffmpeg")) {
+        return Ok(FfmpegLocation {
+            ffmpeg,
+            ffprobe: which::which(binary_name("ffprobe")).ok(),
+            source: "path".to_string(),
+        });
+    }
+
+    // Then binaries bundled in the resources directory, where the download script puts them
+    // in an ffmpeg subdirectory
+    for resource_dir in ytdlp_manager::resource_dir_candidates() {
+        let bundled = find_in_dir(&resource_dir.join("ffmpeg"), "bundled")
+            .or_else(|| find_in_dir(&resource_dir, "bundled"));
+        if let Some(location) = bundled {
+            return Ok(location);
+        }
+    }
+
+    // Finally a user-managed copy in the app's data directory
+    if let Ok(managed_dir) = get_managed_ffmpeg_dir() {
+        if let Some(location) = find_in_dir(&managed_dir, "managed") {
+            return Ok(location);
+        }
+    }
+
+    Err("FFmpeg not found. Install FFmpeg or place ffmpeg and ffprobe in the app's FFmpeg directory.".to_string())
+}
+
+pub async fn get_ffmpeg_version() -> Result<FfmpegVersionInfo, AppError> {
+    let location = get_ffmpeg_location()
+        .await
+        .map_err(|e| AppError::new(ErrorKind::FfmpegMissing, e))?;
+
+    let output = tokio::process::Command::new(&location.ffmpeg)
+        .arg("-version")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute FFmpeg: {}", e))?;
+
+    if !output.status.success() {
+        return Err("Failed to get FFmpeg version".into());
+    }
+
+    // First line looks like "ffmpeg version 6.1.1 Copyright (c) 2000-2023 ..."
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("ffmpeg version "))
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap_or("unknown")
+        .to_string();
+
+    Ok(FfmpegVersionInfo {
+        version,
+        source: location.source,
+        ffmpeg_path: location.ffmpeg.to_string_lossy().to_string(),
+        ffprobe_path: location.ffprobe.map(|path| path.to_string_lossy().to_string()),
+    })
+}
@@ -0,0 +1,149 @@
+use crate::config;
+use crate::error::ErrorKind;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const HISTORY_FILE_NAME: &str = "history.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOutcome {
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub url: String,
+    pub extractor: Option<String>,
+    pub video_id: Option<String>,
+    pub title: Option<String>,
+    // Human-readable format description, e.g. "137 - 1920x1080 (1080p)+140 - audio only"
+    pub format: Option<String>,
+    pub file_path: Option<String>,
+    pub filesize: Option<u64>,
+    // Unix timestamps in seconds
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub outcome: HistoryOutcome,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub error_kind: Option<ErrorKind>,
+}
+
+/// Filter for listing history; all set fields must match
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryFilter {
+    // Case-insensitive match against title, URL, video ID and file path
+    pub query: Option<String>,
+    pub outcome: Option<HistoryOutcome>,
+    pub extractor: Option<String>,
+}
+
+// Serializes read-modify-write cycles on the history file
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_history_path() -> Result<PathBuf, String> {
+    Ok(config::get_config_dir()?.join(HISTORY_FILE_NAME))
+}
+
+fn load_entries() -> Result<Vec<HistoryEntry>, String> {
+    let history_path = get_history_path()?;
+
+    if !history_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&history_path)
+        .map_err(|e| format!("Failed to read history file: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse history file: {}", e))
+}
+
+// The rename replaces the old file in one step, so an interrupted write keeps the previous history
+fn save_entries(entries: &[HistoryEntry]) -> Result<(), String> {
+    let history_path = get_history_path()?;
+    let temp_path = history_path.with_extension("json.tmp");
+
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize history: {}", e))?;
+
+    fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed to write history file: {}", e))?;
+
+    fs::rename(&temp_path, &history_path)
+        .map_err(|e| format!("Failed to write history file: {}", e))
+}
+
+/// Append an entry, assigning it the next free ID
+pub fn add_entry(mut entry: HistoryEntry) -> Result<HistoryEntry, String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut entries = load_entries()?;
+    entry.id = entries.iter().map(|existing| existing.id).max().unwrap_or(0) + 1;
+    entries.push(entry.clone());
+    save_entries(&entries)?;
+
+    Ok(entry)
+}
+
+/// List entries matching the filter, newest first
+pub fn list_entries(filter: &HistoryFilter) -> Result<Vec<HistoryEntry>, String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let query = filter.query.as_ref().map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+    let contains_query = |value: &Option<String>, query: &str| {
+        value.as_ref().is_some_and(|v| v.to_lowercase().contains(query))
+    };
+
+    let mut entries: Vec<HistoryEntry> = load_entries()?
+        .into_iter()
+        .filter(|entry| filter.outcome.is_none_or(|outcome| entry.outcome == outcome))
+        .filter(|entry| {
+            filter.extractor.as_ref().is_none_or(|extractor| {
+                entry.extractor.as_ref().is_some_and(|e| e.eq_ignore_ascii_case(extractor))
+            })
+        })
+        .filter(|entry| {
+            query.as_ref().is_none_or(|query| {
+                entry.url.to_lowercase().contains(query.as_str())
+                    || contains_query(&entry.title, query)
+                    || contains_query(&entry.video_id, query)
+                    || contains_query(&entry.file_path, query)
+            })
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+    Ok(entries)
+}
+
+/// Paths of all downloaded files the history knows about
+pub fn file_paths() -> Result<Vec<String>, String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    Ok(load_entries()?
+        .into_iter()
+        .filter_map(|entry| entry.file_path)
+        .collect())
+}
+
+/// Delete entries by ID. Downloaded files are left untouched.
+pub fn delete_entries(ids: &[u64]) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut entries = load_entries()?;
+    entries.retain(|entry| !ids.contains(&entry.id));
+    save_entries(&entries)
+}
+
+pub fn clear() -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().map_err(|e| format!("Lock error: {}", e))?;
+    save_entries(&[])
+}
@@ -3,8 +3,20 @@
 
 mod commands;
 mod ytdlp_manager;
+mod ffmpeg_manager;
 mod updater;
 mod config;
+mod cleanup;
+mod download_manager;
+mod download_options;
+mod error;
+mod history;
+mod output_template;
+mod playlist;
+mod probe;
+mod queue;
+mod release_cache;
+mod ytdlp_output;
 
 use commands::*;
 
@@ -14,13 +26,51 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             get_video_info,
             get_ytdlp_version,
+            get_ffmpeg_version,
+            get_ffmpeg_managed_dir,
             check_ytdlp_update,
+            clear_release_cache,
             update_ytdlp,
+            cancel_ytdlp_update,
+            rollback_ytdlp,
+            get_update_settings,
+            set_update_settings,
+            list_ytdlp_releases,
+            install_ytdlp_version,
             get_download_location,
             set_download_location,
+            get_max_concurrent_downloads,
+            set_max_concurrent_downloads,
+            get_retry_policy,
+            set_retry_policy,
+            get_cleanup_policy,
+            set_cleanup_policy,
+            scan_leftover_files,
+            delete_leftover_files,
+            get_output_template,
+            set_output_template,
+            list_output_template_presets,
+            validate_output_template,
+            preview_output_template,
             get_video_formats,
+            get_audio_formats,
+            clear_probe_cache,
+            get_playlist_entries,
+            start_playlist_probe,
+            cancel_playlist_probe,
             download_video,
             cancel_download,
+            pause_download,
+            resume_download,
+            list_downloads,
+            list_interrupted_downloads,
+            resume_interrupted_downloads,
+            discard_interrupted_downloads,
+            open_downloaded_file,
+            reveal_downloaded_file,
+            list_history,
+            delete_history_entries,
+            clear_history,
             get_app_version
         ])
         .run(tauri::generate_context!())
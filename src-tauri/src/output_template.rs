@@ -0,0 +1,226 @@
+use crate::error::AppError;
+use crate::probe;
+use crate::ytdlp_manager;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::LazyLock;
+
+// The video ID keeps videos with the same title from overwriting each other
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "%(title)s [%(id)s].%(ext)s";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputTemplatePreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub template: &'static str,
+}
+
+// Names the info JSON files of concurrent previews apart
+static NEXT_PREVIEW_ID: AtomicU64 = AtomicU64::new(1);
+
+// A template field such as %(title)s or %(playlist_index)03d, at the start of the text
+static TEMPLATE_FIELD: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"^%\(([^()]*)\)[-#0+ ]*\d*(\.\d+)?[diouxXeEfFgGcrsaBjlqDSUp]").unwrap()
+});
+
+pub const PRESETS: &[OutputTemplatePreset] = &[
+    OutputTemplatePreset {
+        id: "title_id",
+        name: "Title [ID]",
+        template: DEFAULT_OUTPUT_TEMPLATE,
+    },
+    OutputTemplatePreset {
+        id: "date_title_id",
+        name: "Date - Title [ID]",
+        template: "%(upload_date>%Y-%m-%d)s - %(title)s [%(id)s].%(ext)s",
+    },
+    OutputTemplatePreset {
+        id: "uploader_date_title",
+        name: "Uploader / Date - Title [ID]",
+        template: "%(uploader)s/%(upload_date>%Y-%m-%d)s - %(title)s [%(id)s].%(ext)s",
+    },
+    OutputTemplatePreset {
+        id: "playlist",
+        name: "Playlist / Index - Title [ID]",
+        template: "%(playlist_title|Videos)s/%(playlist_index|00)s - %(title)s [%(id)s].%(ext)s",
+    },
+];
+
+/// File path a template renders to, with warnings about templates that are valid but risky
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputTemplatePreview {
+    pub filename: String,
+    pub warnings: Vec<String>,
+}
+
+/// Check an output template's syntax. Templates are relative to the download directory.
+pub fn validate(template: &str) -> Result<(), String> {
+    parse_fields(template).map(|_| ())
+}
+
+/// Problems a valid template may still cause
+pub fn warnings(template: &str) -> Vec<String> {
+    let Ok(fields) = parse_fields(template) else {
+        return Vec::new();
+    };
+
+    let mut warnings = Vec::new();
+    // The field name ends where a default, replacement or date format starts
+    let has_id = fields
+        .iter()
+        .any(|field| field.split(['|', '&', '>', ',']).next().map(str::trim) == Some("id"));
+    if !has_id {
+        warnings.push(
+            "The template doesn't include the video ID, e.g. [%(id)s], so videos with the same title overwrite each other"
+                .to_string(),
+        );
+    }
+    warnings
+}
+
+/// Names of the template's fields, after checking its syntax
+fn parse_fields(template: &str) -> Result<Vec<&str>, String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Err("Output template can't be empty".to_string());
+    }
+
+    if Path::new(template).is_absolute() || template.split(['/', '\\']).any(|part| part == "..") {
+        return Err("Output template must stay inside the download folder".to_string());
+    }
+
+    // Every % starts either an escaped "%%" or a field such as %(title)s or %(playlist_index)03d
+    let mut rest = template;
+    let mut fields = Vec::new();
+    while let Some(index) = rest.find('%') {
+        rest = &rest[index..];
+        if let Some(after) = rest.strip_prefix("%%") {
+            rest = after;
+            continue;
+        }
+
+        let Some(captures) = TEMPLATE_FIELD.captures(rest) else {
+            let snippet: String = rest.chars().take(20).collect();
+            return Err(format!(
+                "Invalid output template field at '{}': use fields such as %(title)s",
+                snippet
+            ));
+        };
+
+        let name = captures.get(1).map_or("", |name| name.as_str().trim());
+        if name.is_empty() {
+            return Err("Output template fields need a name, e.g. %(title)s".to_string());
+        }
+        fields.push(name);
+        rest = &rest[captures[0].len()..];
+    }
+
+    if !template.contains("%(ext)s") {
+        return Err("Output template must contain %(ext)s".to_string());
+    }
+
+    Ok(fields)
+}
+
+/// Render the file path YT-DLP would use for a URL. The URL's metadata comes from the probe cache,
+/// so previewing a video that was just looked up doesn't extract it again.
+pub async fn preview(url: &str, template: &str, download_dir: &Path) -> Result<OutputTemplatePreview, AppError> {
+    validate(template)?;
+
+    // Make sure the probe result is cached, then hand it to YT-DLP
+    probe::probe_video(url).await?;
+    let info_name = format!("preview-{}", NEXT_PREVIEW_ID.fetch_add(1, Ordering::SeqCst));
+    let info_json = probe::write_info_json(url, &info_name)?
+        .ok_or_else(|| AppError::from("Video details are no longer cached, look up the URL again"))?;
+
+    let output = ytdlp_manager::ytdlp_command()
+        .await?
+        .arg("--load-info-json")
+        .arg(&info_json)
+        .arg("--output")
+        .arg(format!("{}/{}", download_dir.to_string_lossy(), template.trim()))
+        .arg("--print")
+        .arg("filename")
+        .arg("--playlist-items")
+        .arg("1")
+        .arg("--no-warnings")
+        .output()
+        .await
+        .map_err(AppError::spawn_failed);
+
+    let _ = std::fs::remove_file(&info_json);
+    let output = output?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::from_ytdlp(output.status.code(), &error_msg));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let filename = stdout
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .ok_or_else(|| AppError::from("YT-DLP didn't print a filename"))?;
+
+    Ok(OutputTemplatePreview {
+        filename,
+        warnings: warnings(template),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_valid() {
+        for preset in PRESETS {
+            assert_eq!(validate(preset.template), Ok(()), "{}", preset.id);
+            assert!(warnings(preset.template).is_empty(), "{}", preset.id);
+        }
+    }
+
+    #[test]
+    fn accepts_templates_without_id() {
+        assert_eq!(validate("%(title)s.%(ext)s"), Ok(()));
+        assert_eq!(validate("%(playlist_index)03d - %(title).100s 100%%.%(ext)s"), Ok(()));
+        assert_eq!(warnings("%(title)s.%(ext)s").len(), 1);
+    }
+
+    #[test]
+    fn finds_id_with_default_or_format() {
+        assert!(warnings("%(title)s [%(id|unknown)s].%(ext)s").is_empty());
+        assert!(warnings("%(title)s [%( id )s].%(ext)s").is_empty());
+        assert_eq!(warnings("%(display_id)s.%(ext)s").len(), 1);
+    }
+
+    #[test]
+    fn rejects_empty_template() {
+        assert!(validate("").is_err());
+        assert!(validate("   ").is_err());
+    }
+
+    #[test]
+    fn rejects_paths_outside_download_folder() {
+        assert!(validate("/tmp/%(title)s.%(ext)s").is_err());
+        assert!(validate("../%(title)s.%(ext)s").is_err());
+        assert!(validate("videos\\..\\..\\%(title)s.%(ext)s").is_err());
+        assert_eq!(validate("..%(title)s.%(ext)s"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_fields() {
+        assert!(validate("%(title.%(ext)s").is_err());
+        assert!(validate("%(title)z.%(ext)s").is_err());
+        assert!(validate("100% %(title)s.%(ext)s").is_err());
+        assert!(validate("%()s.%(ext)s").is_err());
+    }
+
+    #[test]
+    fn requires_extension() {
+        assert!(validate("%(title)s [%(id)s]").is_err());
+    }
+}
@@ -0,0 +1,295 @@
+use crate::error::AppError;
+use crate::ytdlp_manager;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::sync::oneshot;
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 1000;
+
+pub type ProbeId = u64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistEntry {
+    // 1-based position in the playlist, as used by --playlist-items
+    pub index: u64,
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub duration: Option<u64>,
+    // "public", "unlisted", "private", "needs_auth", ... when the site reports it
+    pub availability: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistPage {
+    pub playlist_id: Option<String>,
+    pub playlist_title: Option<String>,
+    // Total number of entries, when the site reports it
+    pub total_count: Option<u64>,
+    pub entries: Vec<PlaylistEntry>,
+    pub has_more: bool,
+}
+
+/// An entry streamed from a running probe, with the playlist details known so far
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistProbeEntry {
+    pub probe_id: ProbeId,
+    pub playlist_id: Option<String>,
+    pub playlist_title: Option<String>,
+    pub total_count: Option<u64>,
+    pub entry: PlaylistEntry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeState {
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+/// Sent once when a probe ends
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistProbeStatus {
+    pub probe_id: ProbeId,
+    pub state: ProbeState,
+    // Number of entries emitted before the probe ended
+    pub entry_count: u64,
+    pub error: Option<AppError>,
+}
+
+// Cancel senders of the probes that are still running
+static PROBES: Mutex<Vec<(ProbeId, oneshot::Sender<()>)>> = Mutex::new(Vec::new());
+static NEXT_PROBE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Parse one line of `--flat-playlist --dump-json` output
+pub fn parse_entry(entry: &serde_json::Value, fallback_index: u64) -> PlaylistEntry {
+    PlaylistEntry {
+        index: entry["playlist_index"].as_u64().unwrap_or(fallback_index),
+        id: entry["id"].as_str().map(|s| s.to_string()),
+        title: entry["title"].as_str().map(|s| s.to_string()),
+        url: entry["url"]
+            .as_str()
+            .or_else(|| entry["webpage_url"].as_str())
+            .map(|s| s.to_string()),
+        duration: entry["duration"].as_f64().map(|d| d as u64),
+        availability: entry["availability"].as_str().map(|s| s.to_string()),
+    }
+}
+
+/// List a page of playlist entries without extracting each video.
+/// `start` is the 1-based index of the first entry to list.
+pub async fn get_entries(url: &str, start: Option<u64>, count: Option<u64>) -> Result<PlaylistPage, AppError> {
+    let start = start.unwrap_or(1).max(1);
+    let count = count.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    // Ask for one extra entry to find out whether there is another page
+    let end = start.saturating_add(count);
+
+    let output = ytdlp_manager::ytdlp_command()
+        .await?
+        .arg("--flat-playlist")
+        .arg("--dump-json")
+        .arg("--yes-playlist")
+        .arg("--no-warnings")
+        .arg("--playlist-items")
+        .arg(format!("{}-{}", start, end))
+        .arg(url)
+        .output()
+        .await
+        .map_err(AppError::spawn_failed)?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::from_ytdlp(output.status.code(), &error_msg));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut page = PlaylistPage {
+        playlist_id: None,
+        playlist_title: None,
+        total_count: None,
+        entries: Vec::new(),
+        has_more: false,
+    };
+
+    // One JSON object per entry
+    for (offset, line) in stdout.lines().filter(|line| !line.trim().is_empty()).enumerate() {
+        let entry: serde_json::Value = serde_json::from_str(line)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        if page.playlist_id.is_none() {
+            page.playlist_id = entry["playlist_id"].as_str().map(|s| s.to_string());
+            page.playlist_title = entry["playlist_title"].as_str().map(|s| s.to_string());
+            page.total_count = entry["playlist_count"].as_u64();
+        }
+
+        page.entries.push(parse_entry(&entry, start.saturating_add(offset as u64)));
+    }
+
+    if page.entries.len() as u64 > count {
+        page.entries.truncate(count as usize);
+        page.has_more = true;
+    }
+
+    Ok(page)
+}
+
+/// Start listing every entry of a playlist or channel in the background.
+/// Each entry is emitted as a "playlist-entry" event as soon as YT-DLP prints it,
+/// followed by a single "playlist-probe" event when the probe ends.
+pub async fn start_probe(url: &str, window: tauri::Window) -> Result<ProbeId, AppError> {
+    let mut child = ytdlp_manager::ytdlp_command()
+        .await?
+        .arg("--flat-playlist")
+        .arg("--dump-json")
+        .arg("--yes-playlist")
+        .arg("--no-warnings")
+        .arg(url)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(AppError::spawn_failed)?;
+
+    let id = NEXT_PROBE_ID.fetch_add(1, Ordering::SeqCst);
+    let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+    PROBES
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .push((id, cancel_tx));
+
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] Started playlist probe {} for {}", id, url);
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+
+    tokio::spawn(async move {
+        // Collect stderr separately so a full pipe can't stall the entry stream
+        let stderr_task = tokio::spawn(async move {
+            let mut error_output = String::new();
+            let _ = BufReader::new(stderr).read_to_string(&mut error_output).await;
+            error_output
+        });
+
+        let mut lines = BufReader::new(stdout).lines();
+        let mut playlist_id = None;
+        let mut playlist_title = None;
+        let mut total_count = None;
+        let mut entry_count = 0;
+        let mut cancelled = false;
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel_rx => {
+                    let _ = child.kill().await;
+                    cancelled = true;
+                    break;
+                }
+                line = lines.next_line() => {
+                    let Ok(Some(line)) = line else {
+                        break;
+                    };
+                    let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+                        continue;
+                    };
+
+                    if playlist_id.is_none() {
+                        playlist_id = entry["playlist_id"].as_str().map(|s| s.to_string());
+                        playlist_title = entry["playlist_title"].as_str().map(|s| s.to_string());
+                        total_count = entry["playlist_count"].as_u64();
+                    }
+
+                    entry_count += 1;
+                    let _ = window.emit("playlist-entry", PlaylistProbeEntry {
+                        probe_id: id,
+                        playlist_id: playlist_id.clone(),
+                        playlist_title: playlist_title.clone(),
+                        total_count,
+                        entry: parse_entry(&entry, entry_count),
+                    });
+                }
+            }
+        }
+
+        let status = child.wait().await;
+        let error_output = stderr_task.await.unwrap_or_default();
+
+        if let Ok(mut probes) = PROBES.lock() {
+            probes.retain(|(probe_id, _)| *probe_id != id);
+        }
+
+        let (state, error) = match status {
+            _ if cancelled => (ProbeState::Cancelled, None),
+            Ok(status) if status.success() => (ProbeState::Finished, None),
+            Ok(status) => (ProbeState::Failed, Some(AppError::from_ytdlp(status.code(), &error_output))),
+            Err(e) => (ProbeState::Failed, Some(format!("Failed to wait for process: {}", e).into())),
+        };
+
+        #[cfg(debug_assertions)]
+        eprintln!("[DEBUG] Playlist probe {} ended ({:?}) after {} entries", id, state, entry_count);
+
+        let _ = window.emit("playlist-probe", PlaylistProbeStatus {
+            probe_id: id,
+            state,
+            entry_count,
+            error,
+        });
+    });
+
+    Ok(id)
+}
+
+/// Stop a running probe. Entries already emitted stay valid.
+pub fn cancel_probe(probe_id: ProbeId) -> Result<(), String> {
+    let mut probes = PROBES.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let position = probes
+        .iter()
+        .position(|(id, _)| *id == probe_id)
+        .ok_or_else(|| format!("Probe {} is not running", probe_id))?;
+
+    let (_, cancel_tx) = probes.remove(position);
+    let _ = cancel_tx.send(());
+    Ok(())
+}
+
+// One comma-separated part of a --playlist-items selection: an index, a range or a slice
+static PLAYLIST_ITEM: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^-?\d+([-:](-?\d+)?(:-?\d+)?)?$").unwrap());
+
+/// Check a --playlist-items selection such as "1,3,5-7" or "10:20:2"
+pub fn validate_items(items: &str) -> Result<(), String> {
+    let is_valid = !items.trim().is_empty()
+        && items.split(',').all(|part| PLAYLIST_ITEM.is_match(part.trim()));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid playlist selection '{}': use indices and ranges such as 1,3,5-7",
+            items
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_indices_and_ranges() {
+        for items in ["1", "1,3,5-7", "10:20:2", "-5:", "1:-1", " 2 , 4 ", "3-"] {
+            assert_eq!(validate_items(items), Ok(()), "{}", items);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_selections() {
+        for items in ["", " ", "a", "1,,2", "1-2-3", "1;2", "1,2,"] {
+            assert!(validate_items(items).is_err(), "{}", items);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use crate::error::{AppError, ErrorKind};
+use crate::ytdlp_manager;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+// Format URLs in the probe result expire after a few hours, so keep entries well below that
+const PROBE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+// Query parameters that only track where a link was shared from
+const TRACKING_PARAMS: &[&str] = &["si", "feature", "pp", "fbclid", "gclid"];
+
+type ProbeResult = Result<Arc<serde_json::Value>, AppError>;
+
+struct CacheEntry {
+    key: String,
+    created_at: Instant,
+    // Shared by every caller probing the same URL, so concurrent requests run YT-DLP once
+    result: Arc<OnceCell<ProbeResult>>,
+}
+
+static PROBE_CACHE: Mutex<Vec<CacheEntry>> = Mutex::new(Vec::new());
+
+/// Cache key for a URL: trimmed, without fragment or tracking parameters,
+/// with a lowercase scheme and host and no trailing slash
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url.split('#').next().unwrap_or(url);
+
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (url, None),
+    };
+
+    let base = match base.split_once("://") {
+        Some((scheme, rest)) => {
+            let (host, path) = match rest.find('/') {
+                Some(index) => rest.split_at(index),
+                None => (rest, ""),
+            };
+            format!("{}://{}{}", scheme.to_lowercase(), host.to_lowercase(), path)
+        }
+        None => base.to_string(),
+    };
+    let base = base.trim_end_matches('/');
+
+    let mut params: Vec<&str> = query
+        .unwrap_or("")
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or(param);
+            !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name)
+        })
+        .collect();
+    params.sort_unstable();
+
+    if params.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}?{}", base, params.join("&"))
+    }
+}
+
+/// Full YT-DLP metadata for a URL, including formats and subtitles.
+/// Results are cached per normalized URL for a few minutes; failures are not cached.
+pub async fn probe(url: &str) -> ProbeResult {
+    let key = normalize_url(url);
+
+    let result = {
+        let mut cache = PROBE_CACHE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        cache.retain(|entry| entry.created_at.elapsed() < PROBE_CACHE_TTL);
+
+        match cache.iter().find(|entry| entry.key == key) {
+            Some(entry) => entry.result.clone(),
+            None => {
+                let result = Arc::new(OnceCell::new());
+                cache.push(CacheEntry {
+                    key: key.clone(),
+                    created_at: Instant::now(),
+                    result: result.clone(),
+                });
+                result
+            }
+        }
+    };
+
+    let info = result.get_or_init(|| fetch_info(url)).await.clone();
+
+    if info.is_err() {
+        // Let the next call try again
+        if let Ok(mut cache) = PROBE_CACHE.lock() {
+            cache.retain(|entry| !Arc::ptr_eq(&entry.result, &result));
+        }
+    }
+
+    info
+}
+
+/// Like `probe`, but rejects playlists and channels. Their entries are listed with
+/// `playlist::start_probe`, which streams them instead of waiting for one large document.
+pub async fn probe_video(url: &str) -> ProbeResult {
+    let info = probe(url).await?;
+
+    if is_playlist(&info) {
+        return Err(AppError::new(
+            ErrorKind::Playlist,
+            "This URL is a playlist or channel, not a single video. List its entries with the playlist probe instead.",
+        ));
+    }
+
+    Ok(info)
+}
+
+fn is_playlist(info: &serde_json::Value) -> bool {
+    matches!(info["_type"].as_str(), Some("playlist" | "multi_video"))
+}
+
+/// Drop all cached probe results
+pub fn clear_cache() -> Result<(), String> {
+    PROBE_CACHE
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .clear();
+    Ok(())
+}
+
+/// Write the cached probe result for a URL to a file YT-DLP can read with --load-info-json.
+/// Returns None when there is no fresh result, in which case the download extracts again.
+pub fn write_info_json(url: &str, name: &str) -> Result<Option<PathBuf>, String> {
+    let key = normalize_url(url);
+
+    let info = {
+        let cache = PROBE_CACHE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        cache
+            .iter()
+            .filter(|entry| entry.key == key && entry.created_at.elapsed() < PROBE_CACHE_TTL)
+            .find_map(|entry| entry.result.get().and_then(|result| result.as_ref().ok().cloned()))
+    };
+
+    // Playlist probes only hold the first entry, so a download has to extract them itself
+    let Some(info) = info.filter(|info| !is_playlist(info)) else {
+        return Ok(None);
+    };
+
+    let info_dir = std::env::temp_dir().join("mac-ytdlp");
+    std::fs::create_dir_all(&info_dir)
+        .map_err(|e| format!("Failed to create info JSON directory: {}", e))?;
+
+    let info_path = info_dir.join(format!("{}.info.json", name));
+    let content = serde_json::to_string(info.as_ref())
+        .map_err(|e| format!("Failed to serialize info JSON: {}", e))?;
+    std::fs::write(&info_path, content)
+        .map_err(|e| format!("Failed to write info JSON: {}", e))?;
+
+    Ok(Some(info_path))
+}
+
+async fn fetch_info(url: &str) -> ProbeResult {
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] Probing {}", url);
+
+    // -J prints a single JSON document, for playlists as well as single videos. A video URL that
+    // also names a playlist probes just the video, and for real playlists and channels one flat
+    // entry is enough to tell what they are without paging through all of them.
+    let output = ytdlp_manager::ytdlp_command()
+        .await?
+        .arg("-J")
+        .arg("--no-playlist")
+        .arg("--flat-playlist")
+        .arg("--playlist-items")
+        .arg("1")
+        .arg("--no-warnings")
+        .arg(url)
+        .output()
+        .await
+        .map_err(AppError::spawn_failed)?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::from_ytdlp(output.status.code(), &error_msg));
+    }
+
+    let json_output = String::from_utf8(output.stdout)
+        .map_err(|e| format!("Failed to parse YT-DLP output: {}", e))?;
+
+    serde_json::from_str(&json_output)
+        .map(Arc::new)
+        .map_err(|e| format!("Failed to parse JSON: {}", e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_whitespace_fragment_and_trailing_slash() {
+        assert_eq!(
+            normalize_url("  https://www.youtube.com/watch?v=abc#t=10 "),
+            "https://www.youtube.com/watch?v=abc"
+        );
+        assert_eq!(normalize_url("https://vimeo.com/123/"), "https://vimeo.com/123");
+    }
+
+    #[test]
+    fn lowercases_scheme_and_host_only() {
+        assert_eq!(
+            normalize_url("HTTPS://YouTu.be/AbC_dEf"),
+            "https://youtu.be/AbC_dEf"
+        );
+    }
+
+    #[test]
+    fn drops_tracking_parameters() {
+        assert_eq!(
+            normalize_url("https://youtu.be/abc?si=xyz&utm_source=share&feature=shared"),
+            "https://youtu.be/abc"
+        );
+        assert_eq!(
+            normalize_url("https://www.youtube.com/watch?v=abc&pp=ygU&t=42"),
+            "https://www.youtube.com/watch?t=42&v=abc"
+        );
+    }
+
+    #[test]
+    fn parameter_order_does_not_matter() {
+        assert_eq!(
+            normalize_url("https://www.youtube.com/watch?list=PL1&v=abc"),
+            normalize_url("https://www.youtube.com/watch?v=abc&list=PL1")
+        );
+    }
+
+    #[test]
+    fn recognizes_playlist_results() {
+        assert!(is_playlist(&serde_json::json!({ "_type": "playlist", "entries": [] })));
+        assert!(is_playlist(&serde_json::json!({ "_type": "multi_video" })));
+        assert!(!is_playlist(&serde_json::json!({ "_type": "video", "id": "abc" })));
+        assert!(!is_playlist(&serde_json::json!({ "id": "abc" })));
+    }
+}
@@ -0,0 +1,41 @@
+use crate::config;
+use crate::download_manager::DownloadJob;
+use std::fs;
+use std::path::PathBuf;
+
+const QUEUE_FILE_NAME: &str = "queue.json";
+
+fn get_queue_path() -> Result<PathBuf, String> {
+    Ok(config::get_config_dir()?.join(QUEUE_FILE_NAME))
+}
+
+/// Unfinished jobs saved by the previous session
+pub fn load_jobs() -> Result<Vec<DownloadJob>, String> {
+    let queue_path = get_queue_path()?;
+
+    if !queue_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&queue_path)
+        .map_err(|e| format!("Failed to read queue file: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse queue file: {}", e))
+}
+
+/// Replace the saved queue. Written to a temporary file first so a crash mid-write
+/// can't leave a truncated queue behind.
+pub fn save_jobs(jobs: &[DownloadJob]) -> Result<(), String> {
+    let queue_path = get_queue_path()?;
+    let temp_path = queue_path.with_extension("json.tmp");
+
+    let content = serde_json::to_string_pretty(jobs)
+        .map_err(|e| format!("Failed to serialize queue: {}", e))?;
+
+    fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed to write queue file: {}", e))?;
+
+    fs::rename(&temp_path, &queue_path)
+        .map_err(|e| format!("Failed to write queue file: {}", e))
+}
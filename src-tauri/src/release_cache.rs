@@ -0,0 +1,188 @@
+use crate::config;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CACHE_FILE_NAME: &str = "release_cache.json";
+// Responses younger than this are used without contacting GitHub at all
+const FRESH_SECONDS: u64 = 3600; // 1 hour
+// Responses not confirmed for this long are dropped, e.g. release lists of a channel no longer used
+const MAX_AGE_SECONDS: u64 = 30 * 24 * 3600; // 30 days
+
+/// A GitHub response kept for revalidation with If-None-Match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    etag: Option<String>,
+    // Unix timestamp in seconds of the request that returned or confirmed the body
+    fetched_at: u64,
+    body: String,
+}
+
+// Loaded from disk on first use
+static RELEASE_CACHE: Mutex<Option<Vec<CachedResponse>>> = Mutex::new(None);
+
+fn get_cache_path() -> Result<PathBuf, String> {
+    Ok(config::get_config_dir()?.join(CACHE_FILE_NAME))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+fn lock_cache() -> Result<MutexGuard<'static, Option<Vec<CachedResponse>>>, String> {
+    let mut cache = RELEASE_CACHE.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    if cache.is_none() {
+        let entries = load_entries().unwrap_or_else(|_e| {
+            #[cfg(debug_assertions)]
+            eprintln!("[DEBUG] Failed to load release cache: {}", _e);
+            Vec::new()
+        });
+        *cache = Some(entries);
+    }
+
+    Ok(cache)
+}
+
+fn load_entries() -> Result<Vec<CachedResponse>, String> {
+    let cache_path = get_cache_path()?;
+
+    if !cache_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&cache_path)
+        .map_err(|e| format!("Failed to read release cache: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse release cache: {}", e))
+}
+
+fn save_entries(entries: &[CachedResponse]) -> Result<(), String> {
+    let cache_path = get_cache_path()?;
+    let temp_path = cache_path.with_extension("json.tmp");
+
+    let content = serde_json::to_string(entries)
+        .map_err(|e| format!("Failed to serialize release cache: {}", e))?;
+
+    fs::write(&temp_path, content)
+        .map_err(|e| format!("Failed to write release cache: {}", e))?;
+
+    fs::rename(&temp_path, &cache_path)
+        .map_err(|e| format!("Failed to write release cache: {}", e))
+}
+
+/// Store a response, or renew an entry GitHub confirmed is unchanged, and persist the cache
+fn store(url: &str, etag: Option<String>, body: String) -> Result<(), String> {
+    let mut cache = lock_cache()?;
+    let entries = cache.get_or_insert_with(Vec::new);
+    let now = now_secs();
+
+    entries.retain(|entry| entry.url != url && now.saturating_sub(entry.fetched_at) < MAX_AGE_SECONDS);
+    entries.push(CachedResponse {
+        url: url.to_string(),
+        etag,
+        fetched_at: now,
+        body,
+    });
+
+    save_entries(entries)
+}
+
+/// GET a GitHub URL through the cache. Fresh responses are returned without a request unless
+/// `refresh` is set; otherwise the request carries the cached ETag, and a 304 reuses the cached
+/// body without counting against the rate limit. When GitHub can't be reached or refuses the
+/// request (e.g. rate limited), a cached body is returned however old it is.
+/// Returns None for 404 Not Found.
+pub async fn fetch(url: &str, refresh: bool) -> Result<Option<String>, String> {
+    let cached = lock_cache()?
+        .as_ref()
+        .and_then(|entries| entries.iter().find(|entry| entry.url == url).cloned());
+
+    if let Some(cached) = &cached {
+        if !refresh && now_secs().saturating_sub(cached.fetched_at) < FRESH_SECONDS {
+            return Ok(Some(cached.body.clone()));
+        }
+    }
+
+    let client = reqwest::Client::new();
+    let mut request = client.get(url).header("User-Agent", "mac-ytdlp-updater");
+    if let Some(etag) = cached.as_ref().and_then(|cached| cached.etag.as_ref()) {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            return match cached {
+                Some(cached) => {
+                    #[cfg(debug_assertions)]
+                    eprintln!("[DEBUG] Using cached response for {}: {}", url, e);
+                    Ok(Some(cached.body))
+                }
+                None => Err(format!("Failed to fetch {}: {}", url, e)),
+            };
+        }
+    };
+
+    let status = response.status();
+
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        if let Some(cached) = cached {
+            #[cfg(debug_assertions)]
+            eprintln!("[DEBUG] Release info unchanged: {}", url);
+
+            if let Err(_e) = store(url, cached.etag, cached.body.clone()) {
+                #[cfg(debug_assertions)]
+                eprintln!("[DEBUG] Failed to save release cache: {}", _e);
+            }
+            return Ok(Some(cached.body));
+        }
+    }
+
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    if !status.is_success() {
+        return match cached {
+            Some(cached) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[DEBUG] Using cached response for {}: HTTP {}", url, status);
+                Ok(Some(cached.body))
+            }
+            None => Err(format!("Failed to fetch {}: HTTP {}", url, status)),
+        };
+    }
+
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .map(|etag| etag.to_string());
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response from {}: {}", url, e))?;
+
+    if let Err(_e) = store(url, etag, body.clone()) {
+        #[cfg(debug_assertions)]
+        eprintln!("[DEBUG] Failed to save release cache: {}", _e);
+    }
+
+    Ok(Some(body))
+}
+
+/// Forget all cached responses, on disk as well
+pub fn clear() -> Result<(), String> {
+    let mut cache = lock_cache()?;
+    *cache = Some(Vec::new());
+    save_entries(&[])
+}
@@ -1,42 +1,104 @@
+use crate::config::{self, UpdateChannel};
+use crate::error::{AppError, ErrorKind};
+use crate::release_cache;
 use crate::ytdlp_manager;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::sync::OnceLock;
-use std::time::SystemTime;
-
-const YTDLP_RELEASES_API: &str = "https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest";
-
-// Cache for latest version with timestamp
-struct CachedVersion {
-    version: String,
-    cached_at: SystemTime,
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::oneshot;
+
+const GITHUB_REPOS_API: &str = "https://api.github.com/repos";
+const DEFAULT_RELEASE_LIST_LIMIT: u32 = 20;
+// Release asset listing the SHA-256 of every other asset, one "<hash>  <name>" line each
+const CHECKSUMS_ASSET_NAME: &str = "SHA2-256SUMS";
+
+// Minimum time between two "ytdlp-update-progress" events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Stage of the update in progress
+enum UpdatePhase {
+    // Holds the sender that stops the download
+    Downloading(oneshot::Sender<()>),
+    Cancelling,
+    // Verifying and installing the downloaded binary, which can't be cancelled
+    Installing,
 }
 
-static LATEST_VERSION_CACHE: OnceLock<CachedVersion> = OnceLock::new();
-const CACHE_DURATION_SECONDS: u64 = 3600; // 1 hour
+// Set for as long as an update runs. Only one update runs at a time.
+static UPDATE_PHASE: Mutex<Option<UpdatePhase>> = Mutex::new(None);
+
+/// Progress of the binary download during an update, emitted as "ytdlp-update-progress"
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateProgress {
+    // Version being installed
+    pub version: String,
+    pub downloaded_bytes: u64,
+    // Unknown when the server doesn't send a content length
+    pub total_bytes: Option<u64>,
+    // Bytes per second
+    pub speed: Option<f64>,
+}
 
 #[derive(serde::Deserialize)]
 struct GitHubRelease {
     tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
     assets: Vec<GitHubAsset>,
 }
 
+/// A YT-DLP release on the configured channel
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseSummary {
+    pub version: String,
+    pub name: Option<String>,
+    // ISO 8601 timestamp
+    pub published_at: Option<String>,
+    pub prerelease: bool,
+}
+
 #[derive(serde::Deserialize)]
 struct GitHubAsset {
     name: String,
     browser_download_url: String,
 }
 
-pub async fn check_update_available() -> Result<bool, String> {
+/// Whether the installed version differs from the pinned or latest one.
+/// `refresh` revalidates cached release info with GitHub even if it is still fresh.
+pub async fn check_update_available(refresh: bool) -> Result<bool, String> {
     let current_version = get_current_version().await?;
-    let latest_version = get_latest_version().await?;
+    let target_version = match config::load_config().updates.pinned_version {
+        Some(pinned) => pinned,
+        None => get_latest_version(refresh).await?,
+    };
+
+    Ok(normalize_version(&current_version) != normalize_version(&target_version))
+}
 
-    Ok(current_version != latest_version)
+/// Version without a channel prefix such as "nightly@", which matches the release tag
+fn normalize_version(version: &str) -> &str {
+    let version = version.trim();
+    let version = version.rsplit('@').next().unwrap_or(version);
+    version.trim_start_matches('v')
 }
 
 async fn get_current_version() -> Result<String, String> {
     let ytdlp_path = ytdlp_manager::get_ytdlp_path().await?;
-    
-    let output = tokio::process::Command::new(&ytdlp_path)
+    get_binary_version(Path::new(&ytdlp_path)).await
+}
+
+/// Run a YT-DLP binary with --version, which also proves it starts at all
+async fn get_binary_version(path: &Path) -> Result<String, String> {
+    let output = tokio::process::Command::new(path)
         .arg("--version")
         .output()
         .await
@@ -49,42 +111,19 @@ async fn get_current_version() -> Result<String, String> {
     let version = String::from_utf8(output.stdout)
         .map_err(|e| format!("Failed to parse version: {}", e))?;
 
-    Ok(version.trim().to_string())
-}
-
-pub async fn get_latest_version() -> Result<String, String> {
-    // Check cache first
-    if let Some(cached) = LATEST_VERSION_CACHE.get() {
-        if let Ok(elapsed) = SystemTime::now().duration_since(cached.cached_at) {
-            if elapsed.as_secs() < CACHE_DURATION_SECONDS {
-                return Ok(cached.version.clone());
-            }
-        }
+    let version = version.trim();
+    if version.is_empty() {
+        return Err("YT-DLP didn't print a version".to_string());
     }
 
-    // Fetch from API
-    let client = reqwest::Client::new();
-    let response = client
-        .get(YTDLP_RELEASES_API)
-        .header("User-Agent", "mac-ytdlp-updater")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch latest version: {}", e))?;
-
-    let release: GitHubRelease = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse release info: {}", e))?;
-
-    let version = release.tag_name.trim_start_matches('v').to_string();
-    
-    // Update cache
-    let _ = LATEST_VERSION_CACHE.set(CachedVersion {
-        version: version.clone(),
-        cached_at: SystemTime::now(),
-    });
+    Ok(version.to_string())
+}
 
-    Ok(version)
+/// Latest release on the configured channel
+pub async fn get_latest_version(refresh: bool) -> Result<String, String> {
+    let channel = config::load_config().updates.channel;
+    let release = fetch_release(channel, None, refresh).await?;
+    Ok(release.tag_name.trim_start_matches('v').to_string())
 }
 
 /// Compare two yt-dlp version strings
@@ -173,63 +212,325 @@ pub fn compare_ytdlp_versions(version1: &str, version2: &str) -> Result<bool, St
     Ok(true)
 }
 
-pub async fn update_ytdlp() -> Result<String, String> {
+/// Install the latest release on the configured channel, or the pinned version if there is one
+pub async fn update_ytdlp(window: &tauri::Window) -> Result<String, AppError> {
+    let updates = config::load_config().updates;
+    // Make sure "latest" really is the latest release before installing it
+    let release = fetch_release(updates.channel, updates.pinned_version.as_deref(), true).await?;
+
+    let version = install_release(&release, window).await?;
+    Ok(format!("Updated to version {}", version))
+}
+
+/// Install a specific release from the configured channel, e.g. to go back to a known good version
+pub async fn install_version(version: &str, window: &tauri::Window) -> Result<String, AppError> {
+    let channel = config::load_config().updates.channel;
+    let release = fetch_release(channel, Some(version), false).await?;
+
+    let version = install_release(&release, window).await?;
+    Ok(format!("Installed version {}", version))
+}
+
+/// Most recent releases on the configured channel, newest first
+pub async fn list_releases(limit: Option<u32>, refresh: bool) -> Result<Vec<ReleaseSummary>, String> {
+    let channel = config::load_config().updates.channel;
+    let limit = limit.unwrap_or(DEFAULT_RELEASE_LIST_LIMIT).clamp(1, 100);
+    let url = format!("{}/{}/releases?per_page={}", GITHUB_REPOS_API, channel.repository(), limit);
+
+    let body = release_cache::fetch(&url, refresh)
+        .await
+        .map_err(|e| format!("Failed to fetch release list: {}", e))?
+        .ok_or_else(|| format!("No YT-DLP releases found on the {:?} channel", channel))?;
+
+    let releases: Vec<GitHubRelease> = serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse release list: {}", e))?;
+
+    Ok(releases
+        .into_iter()
+        .map(|release| ReleaseSummary {
+            version: release.tag_name.trim_start_matches('v').to_string(),
+            name: release.name,
+            published_at: release.published_at,
+            prerelease: release.prerelease,
+        })
+        .collect())
+}
+
+/// Stop the download of the update in progress. The installed binary is left untouched.
+/// Fails once the download is over, as the new binary is then already being installed.
+pub fn cancel_update() -> Result<(), String> {
+    let mut phase = UPDATE_PHASE.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    match phase.take() {
+        None => Err("No YT-DLP update is in progress".to_string()),
+        Some(UpdatePhase::Downloading(cancel_tx)) => {
+            // The download has already stopped reading from the receiver when the send fails
+            if cancel_tx.send(()).is_err() {
+                *phase = Some(UpdatePhase::Installing);
+                return Err("The YT-DLP update has finished downloading and can no longer be cancelled".to_string());
+            }
+
+            // The update stays in progress until it has stopped
+            *phase = Some(UpdatePhase::Cancelling);
+            Ok(())
+        }
+        Some(UpdatePhase::Cancelling) => {
+            *phase = Some(UpdatePhase::Cancelling);
+            Ok(())
+        }
+        Some(UpdatePhase::Installing) => {
+            *phase = Some(UpdatePhase::Installing);
+            Err("The YT-DLP update has finished downloading and can no longer be cancelled".to_string())
+        }
+    }
+}
+
+/// Stop accepting cancellation once the binary is downloaded, unless it was cancelled just now
+fn finish_download_phase() -> Result<(), AppError> {
+    let mut phase = UPDATE_PHASE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if matches!(*phase, Some(UpdatePhase::Cancelling)) {
+        return Err(AppError::new(ErrorKind::Cancelled, "YT-DLP update was cancelled"));
+    }
+
+    *phase = Some(UpdatePhase::Installing);
+    Ok(())
+}
+
+/// Download, verify and install the platform binary of a release. Returns the installed version.
+async fn install_release(release: &GitHubRelease, window: &tauri::Window) -> Result<String, AppError> {
+    let cancel_rx = {
+        let mut phase = UPDATE_PHASE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if phase.is_some() {
+            return Err("A YT-DLP update is already in progress".into());
+        }
+
+        let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+        *phase = Some(UpdatePhase::Downloading(cancel_tx));
+        cancel_rx
+    };
+
+    let result = download_release(release, window, cancel_rx).await;
+
+    // Only now that the update has stopped can another one start
+    if let Ok(mut phase) = UPDATE_PHASE.lock() {
+        *phase = None;
+    }
+
+    result
+}
+
+async fn download_release(
+    release: &GitHubRelease,
+    window: &tauri::Window,
+    cancel_rx: oneshot::Receiver<()>,
+) -> Result<String, AppError> {
     let resource_dir = ytdlp_manager::get_bundled_ytdlp_dir()?;
     
     // Get the appropriate asset for current platform
     let asset_name = get_platform_asset_name();
-    let download_url = get_download_url(&asset_name).await?;
+    let asset = find_asset(release, &asset_name)?;
+    let expected_checksum = get_expected_checksum(release, &asset.name).await?;
+
+    let target_path = get_target_path(&resource_dir);
+
+    // Ensure resource directory exists
+    fs::create_dir_all(&resource_dir)
+        .map_err(|e| format!("Failed to create resource directory: {}", e))?;
+
+    // Download the new binary next to the live one
+    let staging_path = sibling_path(&target_path, "new");
+    let version = release.tag_name.trim_start_matches('v');
+    let download = download_to_file(&asset.browser_download_url, &staging_path, version, window, cancel_rx)
+        .await
+        .and_then(|checksum| finish_download_phase().map(|_| checksum));
+    let actual_checksum = match download {
+        Ok(checksum) => checksum,
+        Err(e) => {
+            let _ = fs::remove_file(&staging_path);
+            return Err(e);
+        }
+    };
+
+    // Never install a binary that doesn't match the published checksum
+    if actual_checksum != expected_checksum {
+        let _ = fs::remove_file(&staging_path);
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}. The download was not installed.",
+            asset.name, expected_checksum, actual_checksum
+        )
+        .into());
+    }
 
-    // Download the new binary
+    #[cfg(debug_assertions)]
+    eprintln!("[DEBUG] Verified SHA-256 of {}: {}", asset.name, actual_checksum);
+
+    Ok(install_binary(&staging_path, &target_path).await?)
+}
+
+/// Stream a download to `path`, emitting progress to the window. Returns the file's SHA-256.
+async fn download_to_file(
+    url: &str,
+    path: &Path,
+    version: &str,
+    window: &tauri::Window,
+    mut cancel_rx: oneshot::Receiver<()>,
+) -> Result<String, AppError> {
     let client = reqwest::Client::new();
-    let response = client
-        .get(&download_url)
+    let mut response = client
+        .get(url)
         .header("User-Agent", "mac-ytdlp-updater")
         .send()
         .await
-        .map_err(|e| format!("Failed to download YT-DLP: {}", e))?;
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::new(ErrorKind::Network, format!("Failed to download YT-DLP: {}", e)))?;
 
-    let bytes = response
-        .bytes()
+    let mut file = tokio::fs::File::create(path)
         .await
-        .map_err(|e| format!("Failed to read download: {}", e))?;
+        .map_err(|e| format!("Failed to write YT-DLP binary: {}", e))?;
 
-    // Determine the target path
-    let target_path = if cfg!(target_os = "windows") {
-        resource_dir.join("yt-dlp.exe")
-    } else if cfg!(target_os = "macos") {
-        // macOS binary is universal (works for both Intel and Apple Silicon)
-        resource_dir.join("yt-dlp_macos")
-    } else {
-        if cfg!(target_arch = "aarch64") {
-            resource_dir.join("yt-dlp_linux_arm64")
-        } else {
-            resource_dir.join("yt-dlp_linux")
-        }
+    let mut hasher = Sha256::new();
+    let mut progress = UpdateProgress {
+        version: version.to_string(),
+        downloaded_bytes: 0,
+        total_bytes: response.content_length(),
+        speed: None,
     };
+    let started_at = Instant::now();
+    let mut last_emitted_at: Option<Instant> = None;
 
-    // Ensure resource directory exists
-    fs::create_dir_all(&resource_dir)
-        .map_err(|e| format!("Failed to create resource directory: {}", e))?;
+    loop {
+        let chunk = tokio::select! {
+            _ = &mut cancel_rx => {
+                return Err(AppError::new(ErrorKind::Cancelled, "YT-DLP update was cancelled"));
+            }
+            chunk = response.chunk() => chunk
+                .map_err(|e| AppError::new(ErrorKind::Network, format!("Failed to read download: {}", e)))?,
+        };
+
+        let Some(chunk) = chunk else {
+            break;
+        };
+
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write YT-DLP binary: {}", e))?;
+
+        progress.downloaded_bytes += chunk.len() as u64;
+        if last_emitted_at.is_none_or(|emitted_at| emitted_at.elapsed() >= PROGRESS_INTERVAL) {
+            emit_progress(window, &mut progress, started_at);
+            last_emitted_at = Some(Instant::now());
+        }
+    }
 
-    // Write the new binary
-    fs::write(&target_path, bytes)
+    // Make sure the whole binary is on disk before it is renamed into place
+    file.sync_all()
+        .await
         .map_err(|e| format!("Failed to write YT-DLP binary: {}", e))?;
 
+    emit_progress(window, &mut progress, started_at);
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn emit_progress(window: &tauri::Window, progress: &mut UpdateProgress, started_at: Instant) {
+    let elapsed = started_at.elapsed().as_secs_f64();
+    if elapsed > 0.0 {
+        progress.speed = Some(progress.downloaded_bytes as f64 / elapsed);
+    }
+    let _ = window.emit("ytdlp-update-progress", progress.clone());
+}
+
+/// Put the binary from the previous update back in place. The replaced binary becomes
+/// the new backup, so a rollback can itself be undone.
+pub async fn rollback_ytdlp() -> Result<String, String> {
+    let target_path = get_target_path(&ytdlp_manager::get_bundled_ytdlp_dir()?);
+    let backup_path = sibling_path(&target_path, "bak");
+
+    if !backup_path.exists() {
+        return Err("There is no previous YT-DLP version to roll back to".to_string());
+    }
+
+    let version = get_binary_version(&backup_path)
+        .await
+        .map_err(|e| format!("The previous YT-DLP version doesn't run: {}", e))?;
+
+    // Copy the current binary aside first, so the rename below can replace it atomically
+    let staging_path = sibling_path(&target_path, "rollback");
+    if target_path.exists() {
+        fs::copy(&target_path, &staging_path)
+            .map_err(|e| format!("Failed to back up current YT-DLP binary: {}", e))?;
+    }
+
+    if let Err(e) = fs::rename(&backup_path, &target_path) {
+        let _ = fs::remove_file(&staging_path);
+        return Err(format!("Failed to restore previous YT-DLP binary: {}", e));
+    }
+
+    if staging_path.exists() {
+        fs::rename(&staging_path, &backup_path)
+            .map_err(|e| format!("Failed to keep replaced YT-DLP binary as backup: {}", e))?;
+    }
+
+    Ok(format!("Rolled back to version {}", version))
+}
+
+/// Install a verified download, written next to the target, over the target. The binary is
+/// checked to run and renamed into place, so the live binary is never partially written.
+/// The replaced binary is kept as a `.bak` file for rollback.
+async fn install_binary(staging_path: &Path, target_path: &Path) -> Result<String, String> {
+    let version = match prepare_binary(staging_path).await {
+        Ok(version) => version,
+        Err(e) => {
+            let _ = fs::remove_file(staging_path);
+            return Err(format!("The downloaded YT-DLP doesn't run, keeping the current version: {}", e));
+        }
+    };
+
+    if target_path.exists() {
+        let backup_path = sibling_path(target_path, "bak");
+        if let Err(e) = fs::copy(target_path, &backup_path) {
+            let _ = fs::remove_file(staging_path);
+            return Err(format!("Failed to back up current YT-DLP binary: {}", e));
+        }
+    }
+
+    if let Err(e) = fs::rename(staging_path, target_path) {
+        let _ = fs::remove_file(staging_path);
+        return Err(format!("Failed to install YT-DLP binary: {}", e));
+    }
+
+    Ok(version)
+}
+
+/// Make a freshly written binary executable and return the version it reports
+async fn prepare_binary(path: &Path) -> Result<String, String> {
     // Make executable on Unix systems
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&target_path)
+        let mut perms = fs::metadata(path)
             .map_err(|e| format!("Failed to get file metadata: {}", e))?
             .permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&target_path, perms)
+        fs::set_permissions(path, perms)
             .map_err(|e| format!("Failed to set permissions: {}", e))?;
     }
 
-    let version = get_latest_version().await?;
-    Ok(format!("Updated to version {}", version))
+    get_binary_version(path).await
+}
+
+/// Where the bundled binary for this platform lives
+fn get_target_path(resource_dir: &Path) -> PathBuf {
+    resource_dir.join(get_platform_asset_name())
+}
+
+/// A file next to `path` with an extra extension, e.g. `yt-dlp_macos.bak`
+fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
 }
 
 fn get_platform_asset_name() -> String {
@@ -247,31 +548,75 @@ fn get_platform_asset_name() -> String {
     }
 }
 
-async fn get_download_url(asset_name: &str) -> Result<String, String> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get(YTDLP_RELEASES_API)
-        .header("User-Agent", "mac-ytdlp-updater")
-        .send()
+/// A release from the channel's repository: the given version, or the latest one.
+/// Served from the release cache; `refresh` revalidates it with GitHub even while fresh.
+async fn fetch_release(channel: UpdateChannel, version: Option<&str>, refresh: bool) -> Result<GitHubRelease, String> {
+    let releases_api = format!("{}/{}/releases", GITHUB_REPOS_API, channel.repository());
+    let url = match version {
+        Some(version) => {
+            config::validate_version(version)?;
+            format!("{}/tags/{}", releases_api, normalize_version(version))
+        }
+        None => format!("{}/latest", releases_api),
+    };
+
+    let body = release_cache::fetch(&url, refresh)
         .await
-        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
+        .map_err(|e| format!("Failed to fetch release info: {}", e))?
+        .ok_or_else(|| match version {
+            Some(version) => format!("YT-DLP version {} was not found on the {:?} channel", version, channel),
+            None => format!("No YT-DLP release found on the {:?} channel", channel),
+        })?;
+
+    serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse release info: {}", e))
+}
 
-    let release: GitHubRelease = response
-        .json()
+/// The SHA-256 the release publishes for an asset, as lowercase hex
+async fn get_expected_checksum(release: &GitHubRelease, asset_name: &str) -> Result<String, String> {
+    let checksums_asset = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == CHECKSUMS_ASSET_NAME)
+        .ok_or_else(|| {
+            format!(
+                "Release {} has no {} file, so the download can't be verified",
+                release.tag_name, CHECKSUMS_ASSET_NAME
+            )
+        })?;
+
+    // A release's checksums never change, so the cached copy is as good as a new one
+    let checksums = release_cache::fetch(&checksums_asset.browser_download_url, false)
         .await
-        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+        .map_err(|e| format!("Failed to download checksums: {}", e))?
+        .ok_or_else(|| format!("{} of release {} was not found", CHECKSUMS_ASSET_NAME, release.tag_name))?;
+
+    parse_checksum(&checksums, asset_name)
+        .ok_or_else(|| format!("No checksum published for {}, so the download can't be verified", asset_name))
+}
+
+fn parse_checksum(checksums: &str, asset_name: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        // sha256sum marks binary-mode entries with a leading '*'
+        let name = name.trim().trim_start_matches('*');
+        let is_sha256 = hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+        (name == asset_name && is_sha256).then(|| hash.to_lowercase())
+    })
+}
 
+fn find_asset<'a>(release: &'a GitHubRelease, asset_name: &str) -> Result<&'a GitHubAsset, String> {
     // First, try exact match
     for asset in &release.assets {
         if asset.name == asset_name {
-            return Ok(asset.browser_download_url.clone());
+            return Ok(asset);
         }
     }
 
     // Then try partial match
     for asset in &release.assets {
         if asset.name.contains(asset_name) && !asset.name.contains(".sig") {
-            return Ok(asset.browser_download_url.clone());
+            return Ok(asset);
         }
     }
 
@@ -304,7 +649,7 @@ async fn get_download_url(asset_name: &str) -> Result<String, String> {
                 let is_unix_binary = !cfg!(target_os = "windows") && !asset.name.ends_with(".exe");
                 
                 if is_windows_exe || is_unix_binary {
-                    return Ok(asset.browser_download_url.clone());
+                    return Ok(asset);
                 }
             }
         }
@@ -313,3 +658,45 @@ async fn get_download_url(asset_name: &str) -> Result<String, String> {
     Err(format!("No suitable YT-DLP binary found for platform: {}", asset_name))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "6b86b273ff34fce19d6b804eff5a3f5747ada4eaa22f1d49c01e52ddb7875b4b";
+
+    #[test]
+    fn finds_checksum_of_asset() {
+        let checksums = format!(
+            "{}  yt-dlp.exe\n{}  yt-dlp_macos\n",
+            "d4735e3a265e16eee03f59718b9b5d03019c07d8b6c51f90da3a666eec13ab35", HASH
+        );
+        assert_eq!(parse_checksum(&checksums, "yt-dlp_macos"), Some(HASH.to_string()));
+    }
+
+    #[test]
+    fn matches_asset_names_exactly() {
+        let checksums = format!("{}  yt-dlp_macos.zip\n", HASH);
+        assert_eq!(parse_checksum(&checksums, "yt-dlp_macos"), None);
+        assert_eq!(parse_checksum(&checksums, "yt-dlp"), None);
+    }
+
+    #[test]
+    fn accepts_binary_mode_and_uppercase_entries() {
+        let checksums = format!("{} *yt-dlp\r\n", HASH.to_uppercase());
+        assert_eq!(parse_checksum(&checksums, "yt-dlp"), Some(HASH.to_string()));
+    }
+
+    #[test]
+    fn rejects_malformed_hashes() {
+        assert_eq!(parse_checksum("abc123  yt-dlp\n", "yt-dlp"), None);
+        assert_eq!(parse_checksum(&format!("{}  yt-dlp\n", HASH.replacen('6', "z", 1)), "yt-dlp"), None);
+        assert_eq!(parse_checksum("", "yt-dlp"), None);
+    }
+
+    #[test]
+    fn normalizes_versions_to_tags() {
+        assert_eq!(normalize_version(" 2024.03.10 "), "2024.03.10");
+        assert_eq!(normalize_version("nightly@2024.03.10.232833"), "2024.03.10.232833");
+        assert_eq!(normalize_version("v2024.03.10"), "2024.03.10");
+    }
+}
@@ -1,6 +1,8 @@
 use std::path::{Path, PathBuf};
 use std::env;
 use tokio::sync::OnceCell;
+use crate::error::{AppError, ErrorKind};
+use crate::ffmpeg_manager;
 
 // Cache the YT-DLP path to avoid repeated lookups
 static YTDLP_PATH_CACHE: OnceCell<Result<PathBuf, String>> = OnceCell::const_new();
@@ -13,6 +15,20 @@ pub async fn get_ytdlp_path() -> Result<PathBuf, String> {
         .clone()
 }
 
+/// Build a YT-DLP command, passing along the located FFmpeg when there is one
+pub async fn ytdlp_command() -> Result<tokio::process::Command, AppError> {
+    let ytdlp_path = get_ytdlp_path()
+        .await
+        .map_err(|e| AppError::new(ErrorKind::BinaryMissing, format!("Failed to get YT-DLP path: {}", e)))?;
+
+    let mut cmd = tokio::process::Command::new(&ytdlp_path);
+    if let Ok(ffmpeg) = ffmpeg_manager::get_ffmpeg_location().await {
+        cmd.arg("--ffmpeg-location").arg(ffmpeg.ytdlp_location());
+    }
+
+    Ok(cmd)
+}
+
 async fn find_ytdlp_path() -> Result<PathBuf, String> {
     // First, check system PATH for yt-dlp
     let system_path = if cfg!(target_os = "windows") {
@@ -29,11 +45,22 @@ async fn find_ytdlp_path() -> Result<PathBuf, String> {
     }
 
     // Fallback to bundled YT-DLP in resources directory
+    for resource_dir in resource_dir_candidates() {
+        let bundled_path = get_platform_specific_path(&resource_dir);
+        if bundled_path.exists() {
+            return Ok(bundled_path);
+        }
+    }
+
+    Err("YT-DLP not found. Please ensure YT-DLP is installed or bundled with the application.".to_string())
+}
+
+/// Directories that may hold bundled binaries, in order of likelihood (most likely first)
+pub fn resource_dir_candidates() -> Vec<PathBuf> {
+    let mut resource_paths = Vec::new();
+
     if let Ok(exe_path) = env::current_exe() {
         if let Some(exe_dir) = exe_path.parent() {
-            // Build resource paths in order of likelihood (most likely first)
-            let mut resource_paths = Vec::new();
-
             // On macOS, prioritize .app bundle paths
             #[cfg(target_os = "macos")]
             {
@@ -57,18 +84,10 @@ async fn find_ytdlp_path() -> Result<PathBuf, String> {
             // Add fallback paths
             resource_paths.push(exe_dir.join("../../resources"));
             resource_paths.push(PathBuf::from("src-tauri/resources"));
-
-            // Check paths in order
-            for resource_dir in resource_paths {
-                let bundled_path = get_platform_specific_path(&resource_dir);
-                if bundled_path.exists() {
-                    return Ok(bundled_path);
-                }
-            }
         }
     }
 
-    Err("YT-DLP not found. Please ensure YT-DLP is installed or bundled with the application.".to_string())
+    resource_paths
 }
 
 /// Check if system yt-dlp version is up to date
@@ -96,7 +115,7 @@ async fn check_system_ytdlp_version(ytdlp_path: &PathBuf) -> Result<bool, String
     let system_version = system_version.trim();
 
     // Get latest version from GitHub (uses cache)
-    let latest_version = updater::get_latest_version().await?;
+    let latest_version = updater::get_latest_version(false).await?;
 
     // Compare versions
     updater::compare_ytdlp_versions(system_version, &latest_version)
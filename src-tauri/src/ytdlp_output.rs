@@ -0,0 +1,270 @@
+use crate::download_manager::JobId;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+// Prefixes that mark our machine-readable lines among YT-DLP's regular console output
+const DOWNLOAD_PROGRESS_MARKER: &str = "[mac-ytdlp:download]";
+const POSTPROCESS_PROGRESS_MARKER: &str = "[mac-ytdlp:postprocess]";
+const VIDEO_MARKER: &str = "[mac-ytdlp:video]";
+const FILEPATH_MARKER: &str = "[mac-ytdlp:filepath]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressPhase {
+    Downloading,
+    Finished,
+    Error,
+    Postprocessing,
+}
+
+/// Typed progress update for a job, parsed from YT-DLP's progress template output
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub job_id: JobId,
+    pub phase: ProgressPhase,
+    pub downloaded_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    // True when total_bytes is YT-DLP's estimate rather than a known size
+    pub total_bytes_estimated: bool,
+    // Bytes per second
+    pub speed: Option<f64>,
+    // Seconds remaining
+    pub eta: Option<u64>,
+    pub fragment_index: Option<u64>,
+    pub fragment_count: Option<u64>,
+    pub filename: Option<String>,
+    // Name of the running postprocessor (e.g. "Merger") during the postprocessing phase
+    pub postprocessor: Option<String>,
+}
+
+/// Video details YT-DLP prints just before downloading
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrintedVideo {
+    pub id: Option<String>,
+    // Page of the video itself, which differs from the job URL for playlist items
+    #[serde(default)]
+    pub webpage_url: Option<String>,
+    pub extractor: Option<String>,
+    pub title: Option<String>,
+    pub format_id: Option<String>,
+    pub format: Option<String>,
+}
+
+/// A classified line of YT-DLP output
+pub enum OutputLine {
+    Progress(DownloadProgress),
+    Video(PrintedVideo),
+    // Final path of a file after YT-DLP moved it into place
+    FilePath(String),
+    Console,
+}
+
+/// Arguments that make YT-DLP print the video details and final file path on their own lines
+pub fn print_args() -> Vec<String> {
+    vec![
+        "--print".to_string(),
+        format!("before_dl:{} %(.{{id,webpage_url,extractor,title,format_id,format}})j", VIDEO_MARKER),
+        "--print".to_string(),
+        format!("after_move:{} %(filepath)j", FILEPATH_MARKER),
+        // --print would otherwise turn the run into a simulation, and silence the console output
+        // that names the files being written (see `parse_destination`)
+        "--no-simulate".to_string(),
+        "--no-quiet".to_string(),
+    ]
+}
+
+/// Classify a line of YT-DLP output
+pub fn parse_line(job_id: JobId, line: &str) -> OutputLine {
+    if let Some(progress) = parse_progress_line(job_id, line) {
+        return OutputLine::Progress(progress);
+    }
+
+    if let Some(json) = line.strip_prefix(VIDEO_MARKER) {
+        if let Ok(video) = serde_json::from_str::<PrintedVideo>(json.trim()) {
+            return OutputLine::Video(video);
+        }
+    }
+
+    if let Some(json) = line.strip_prefix(FILEPATH_MARKER) {
+        if let Ok(path) = serde_json::from_str::<String>(json.trim()) {
+            return OutputLine::FilePath(path);
+        }
+    }
+
+    OutputLine::Console
+}
+
+// "[download] Destination: <path>", also printed by postprocessors such as ExtractAudio
+static DESTINATION_LINE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^\[\w+\] Destination: (.+)$").unwrap());
+static MERGER_LINE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r#"^\[Merger\] Merging formats into "(.+)"$"#).unwrap());
+
+/// File a console line says YT-DLP is about to write, e.g. a format stream or the merged output
+pub fn parse_destination(line: &str) -> Option<String> {
+    let line = line.trim_end();
+    DESTINATION_LINE
+        .captures(line)
+        .or_else(|| MERGER_LINE.captures(line))
+        .map(|captures| captures[1].to_string())
+}
+
+/// Arguments that make YT-DLP print progress as one JSON object per line
+pub fn progress_template_args() -> Vec<String> {
+    vec![
+        "--progress-template".to_string(),
+        format!("download:{} %(progress)j", DOWNLOAD_PROGRESS_MARKER),
+        "--progress-template".to_string(),
+        format!("postprocess:{} %(progress)j", POSTPROCESS_PROGRESS_MARKER),
+    ]
+}
+
+/// Parse a progress template line. Returns None for regular console output.
+fn parse_progress_line(job_id: JobId, line: &str) -> Option<DownloadProgress> {
+    let (json, is_postprocess) = if let Some(rest) = line.strip_prefix(DOWNLOAD_PROGRESS_MARKER) {
+        (rest, false)
+    } else if let Some(rest) = line.strip_prefix(POSTPROCESS_PROGRESS_MARKER) {
+        (rest, true)
+    } else {
+        return None;
+    };
+
+    let progress: serde_json::Value = serde_json::from_str(json.trim()).ok()?;
+    let status = progress["status"].as_str().unwrap_or("");
+
+    let phase = if is_postprocess {
+        ProgressPhase::Postprocessing
+    } else {
+        match status {
+            "finished" => ProgressPhase::Finished,
+            "error" => ProgressPhase::Error,
+            _ => ProgressPhase::Downloading,
+        }
+    };
+
+    // YT-DLP reports sizes and times as either integers or floats depending on the downloader
+    let as_u64 = |key: &str| progress[key].as_f64().map(|value| value as u64);
+
+    let (total_bytes, total_bytes_estimated) = match as_u64("total_bytes") {
+        Some(total) => (Some(total), false),
+        None => {
+            let estimate = as_u64("total_bytes_estimate");
+            (estimate, estimate.is_some())
+        }
+    };
+
+    Some(DownloadProgress {
+        job_id,
+        phase,
+        downloaded_bytes: as_u64("downloaded_bytes"),
+        total_bytes,
+        total_bytes_estimated,
+        speed: progress["speed"].as_f64(),
+        eta: as_u64("eta"),
+        fragment_index: as_u64("fragment_index"),
+        fragment_count: as_u64("fragment_count"),
+        filename: progress["filename"].as_str().map(|s| s.to_string()),
+        postprocessor: progress["postprocessor"].as_str().map(|s| s.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(line: &str) -> DownloadProgress {
+        match parse_line(7, line) {
+            OutputLine::Progress(progress) => progress,
+            _ => panic!("not a progress line: {}", line),
+        }
+    }
+
+    #[test]
+    fn parses_download_progress() {
+        let progress = progress(
+            r#"[mac-ytdlp:download] {"status": "downloading", "downloaded_bytes": 1024, "total_bytes": 4096, "speed": 512.5, "eta": 6.0, "filename": "a.mp4"}"#,
+        );
+
+        assert_eq!(progress.job_id, 7);
+        assert_eq!(progress.phase, ProgressPhase::Downloading);
+        assert_eq!(progress.downloaded_bytes, Some(1024));
+        assert_eq!(progress.total_bytes, Some(4096));
+        assert!(!progress.total_bytes_estimated);
+        assert_eq!(progress.speed, Some(512.5));
+        assert_eq!(progress.eta, Some(6));
+        assert_eq!(progress.filename.as_deref(), Some("a.mp4"));
+    }
+
+    #[test]
+    fn falls_back_to_estimated_total() {
+        let progress = progress(
+            r#"[mac-ytdlp:download] {"status": "downloading", "total_bytes_estimate": 2048.7, "fragment_index": 3, "fragment_count": 10, "speed": null}"#,
+        );
+
+        assert_eq!(progress.total_bytes, Some(2048));
+        assert!(progress.total_bytes_estimated);
+        assert_eq!(progress.speed, None);
+        assert_eq!(progress.fragment_index, Some(3));
+        assert_eq!(progress.fragment_count, Some(10));
+    }
+
+    #[test]
+    fn maps_status_to_phase() {
+        let finished = progress(r#"[mac-ytdlp:download] {"status": "finished"}"#);
+        assert_eq!(finished.phase, ProgressPhase::Finished);
+
+        let error = progress(r#"[mac-ytdlp:download] {"status": "error"}"#);
+        assert_eq!(error.phase, ProgressPhase::Error);
+
+        let postprocess = progress(r#"[mac-ytdlp:postprocess] {"status": "started", "postprocessor": "Merger"}"#);
+        assert_eq!(postprocess.phase, ProgressPhase::Postprocessing);
+        assert_eq!(postprocess.postprocessor.as_deref(), Some("Merger"));
+    }
+
+    #[test]
+    fn treats_other_lines_as_console_output() {
+        for line in [
+            "[youtube] abc: Downloading webpage",
+            "[download] Destination: /Downloads/a.mp4",
+            "[mac-ytdlp:download] not json",
+            "",
+        ] {
+            assert!(matches!(parse_line(1, line), OutputLine::Console), "{}", line);
+        }
+    }
+
+    #[test]
+    fn parses_printed_video_and_file_path() {
+        let line = r#"[mac-ytdlp:video] {"id": "abc", "webpage_url": "https://youtu.be/abc", "title": "Title", "format_id": "22"}"#;
+        let OutputLine::Video(video) = parse_line(1, line) else {
+            panic!("not a video line");
+        };
+        assert_eq!(video.id.as_deref(), Some("abc"));
+        assert_eq!(video.webpage_url.as_deref(), Some("https://youtu.be/abc"));
+        assert_eq!(video.extractor, None);
+
+        let OutputLine::FilePath(path) = parse_line(1, r#"[mac-ytdlp:filepath] "/Downloads/Title [abc].mp4""#) else {
+            panic!("not a file path line");
+        };
+        assert_eq!(path, "/Downloads/Title [abc].mp4");
+
+        assert!(matches!(parse_line(1, "[mac-ytdlp:filepath] unquoted"), OutputLine::Console));
+    }
+
+    #[test]
+    fn parses_destination_lines() {
+        assert_eq!(
+            parse_destination("[download] Destination: /Downloads/Title [abc].f137.mp4\n").as_deref(),
+            Some("/Downloads/Title [abc].f137.mp4")
+        );
+        assert_eq!(
+            parse_destination("[ExtractAudio] Destination: /Downloads/Title [abc].mp3").as_deref(),
+            Some("/Downloads/Title [abc].mp3")
+        );
+        assert_eq!(
+            parse_destination(r#"[Merger] Merging formats into "/Downloads/Title [abc].mp4""#).as_deref(),
+            Some("/Downloads/Title [abc].mp4")
+        );
+        assert_eq!(parse_destination("[download] 100% of 10.00MiB"), None);
+    }
+}
@@ -1,11 +1,14 @@
 import { type Writable, writable } from 'svelte/store';
 
-import type { DownloadState } from '$lib/types';
+import type { AppError, DownloadJob, DownloadOptions, DownloadOutput, DownloadProgress, DownloadState } from '$lib/types';
 
+import { describeError, isAppError } from '$lib/utils/errors';
 import {
 	type ProgressUnlisten,
 	cancelDownload,
 	downloadVideo,
+	pauseDownload,
+	resumeDownload,
 	setupProgressListener,
 } from '$lib/utils/tauri';
 
@@ -13,54 +16,72 @@ const MAX_OUTPUT_LINES = 3;
 
 type DownloadStore = {
 	subscribe: Writable<DownloadState>['subscribe'];
-	start: (url: string, quality: string) => Promise<void>;
+	start: (url: string, quality: string, options?: DownloadOptions) => Promise<void>;
 	cancel: () => Promise<void>;
+	pause: () => Promise<void>;
+	resume: () => Promise<void>;
 	setStatus: (status: string, type?: 'muted' | 'primary' | 'success' | 'error') => void;
 	reset: () => void;
 };
 
 function createDownloadStore(): DownloadStore {
 	const { subscribe, set, update } = writable<DownloadState>({
+		jobId: null,
 		inProgress: false,
 		active: false,
 		outputLines: [],
+		progress: null,
+		filePath: null,
 		status: '',
 		statusType: 'muted',
 		buttonText: 'Download Video',
 		showCancel: false,
 		showProgress: false,
+		paused: false,
 	});
 
 	let progressUnlisten: ProgressUnlisten | null = null;
 	let outputLines: string[] = [];
+	let currentJobId: number | null = null;
 
 	return {
 		subscribe,
-		start: async (url: string, quality: string): Promise<void> => {
+		start: async (url: string, quality: string, options?: DownloadOptions): Promise<void> => {
 			// Reset output lines
 			outputLines = [];
+			currentJobId = null;
 
 			set({
+				jobId: null,
 				inProgress: true,
 				active: true,
 				outputLines: [],
+				progress: null,
+				filePath: null,
 				status: 'Downloading video...',
 				statusType: 'primary',
 				buttonText: 'Downloading...',
 				showCancel: true,
 				showProgress: false,
+				paused: false,
 			});
 
+			// Job updates can arrive before the job ID is returned, so remember finished jobs
+			const finishedJobs = new Map<number, DownloadJob>();
+			let notifyFinished: () => void = () => {};
+
 			// Setup progress listener
 			try {
 				progressUnlisten = await setupProgressListener(
-					(line: string) => {
+					(output: DownloadOutput) => {
+						if (output.job_id !== currentJobId) { return; }
+
 						// Only update if download is still active
 						update((state) => {
 							if (!state.active) { return state; }
 
 							// Create new array with the new line
-							const newLines = [...state.outputLines, line];
+							const newLines = [...state.outputLines, output.line];
 							if (newLines.length > MAX_OUTPUT_LINES) {
 								newLines.shift();
 							}
@@ -75,8 +96,27 @@ function createDownloadStore(): DownloadStore {
 							};
 						});
 					},
-					(error: string) => {
-						console.error('[DEBUG] Download error event:', error);
+					(progress: DownloadProgress) => {
+						if (progress.job_id !== currentJobId) { return; }
+
+						update((state) => {
+							if (!state.active) { return state; }
+							return { ...state, progress, showProgress: true };
+						});
+					},
+					(job: DownloadJob) => {
+						if (job.id === currentJobId && (job.state === 'queued' || job.state === 'running' || job.state === 'paused')) {
+							const paused = job.state === 'paused';
+							// The message explains e.g. a retry after a transient failure
+							const status = paused ? 'Download paused' : job.message ?? 'Downloading video...';
+							const statusType = paused || job.message ? 'muted' : 'primary';
+							update((state) => { return { ...state, paused, status, statusType }; });
+							return;
+						}
+						if (job.state !== 'finished' && job.state !== 'failed' && job.state !== 'cancelled') { return; }
+
+						finishedJobs.set(job.id, job);
+						notifyFinished();
 					},
 				);
 			} catch (error) {
@@ -84,16 +124,37 @@ function createDownloadStore(): DownloadStore {
 			}
 
 			try {
-				const result = await downloadVideo(url, quality);
+				// The URL was just probed for its formats, so let the download reuse that result
+				const jobId = await downloadVideo(url, quality, { reuse_probe: true, ...options });
+				currentJobId = jobId;
+				update((state) => { return { ...state, jobId }; });
+
+				const job = finishedJobs.get(jobId) ?? await new Promise<DownloadJob>((resolve) => {
+					notifyFinished = (): void => {
+						const result = finishedJobs.get(jobId);
+						if (result) { resolve(result); }
+					};
+				});
+				if (job.state === 'cancelled') {
+					throw { kind: 'cancelled', message: 'Download cancelled' } satisfies AppError;
+				}
+				if (job.state !== 'finished') {
+					throw job.error ?? new Error(job.message || 'Download failed');
+				}
+
 				set({
+					jobId: job.id,
 					inProgress: false,
 					active: false,
 					outputLines: [],
-					status: result,
+					progress: null,
+					filePath: job.result?.file_paths.at(-1) ?? null,
+					status: job.message || 'Download completed',
 					statusType: 'success',
 					buttonText: 'Download Video',
 					showCancel: false,
 					showProgress: false,
+					paused: false,
 				});
 
 				// Auto-hide progress after 2 seconds
@@ -103,21 +164,25 @@ function createDownloadStore(): DownloadStore {
 							...state,
 							showProgress: false,
 							outputLines: [],
+							progress: null,
 						};
 					});
 				}, 2000);
 			} catch (error) {
-				const errorString = error instanceof Error ? error.message : String(error);
-				const isCancelled = errorString.includes('cancelled') || errorString.includes('Cancel');
+				const isCancelled = isAppError(error) && error.kind === 'cancelled';
 				set({
+					jobId: currentJobId,
 					inProgress: false,
 					active: false,
 					outputLines: [],
-					status: isCancelled ? 'Download cancelled' : `Download failed: ${errorString}`,
+					progress: null,
+					filePath: null,
+					status: isCancelled ? 'Download cancelled' : `Download failed: ${describeError(error)}`,
 					statusType: isCancelled ? 'muted' : 'error',
 					buttonText: 'Download Video',
 					showCancel: false,
 					showProgress: false,
+					paused: false,
 				});
 			} finally {
 				if (progressUnlisten) {
@@ -134,42 +199,68 @@ function createDownloadStore(): DownloadStore {
 			}
 		},
 		cancel: async (): Promise<void> => {
-			if (progressUnlisten) {
-				await progressUnlisten();
-				progressUnlisten = null;
-			}
+			// The listener stays registered so start() sees the cancelled job update and cleans up
+			if (currentJobId === null) { return; }
 
 			try {
-				await cancelDownload();
+				await cancelDownload(currentJobId);
 				set({
+					jobId: currentJobId,
 					inProgress: false,
 					active: false,
 					outputLines: [],
+					progress: null,
+					filePath: null,
 					status: 'Download cancelled',
 					statusType: 'muted',
 					buttonText: 'Download Video',
 					showCancel: false,
 					showProgress: false,
+					paused: false,
 				});
 			} catch (error) {
 				console.error('Failed to cancel download:', error);
 			}
 		},
+		pause: async (): Promise<void> => {
+			// The job update event switches the state to paused once YT-DLP has stopped
+			if (currentJobId === null) { return; }
+
+			try {
+				await pauseDownload(currentJobId);
+			} catch (error) {
+				console.error('Failed to pause download:', error);
+			}
+		},
+		resume: async (): Promise<void> => {
+			if (currentJobId === null) { return; }
+
+			try {
+				await resumeDownload(currentJobId);
+			} catch (error) {
+				console.error('Failed to resume download:', error);
+			}
+		},
 		setStatus: (status: string, type: 'muted' | 'primary' | 'success' | 'error' = 'muted'): void => {
 			update((state) => { return { ...state, status, statusType: type }; });
 		},
 		reset: (): void => {
 			set({
+				jobId: null,
 				inProgress: false,
 				active: false,
 				outputLines: [],
+				progress: null,
+				filePath: null,
 				status: '',
 				statusType: 'muted',
 				buttonText: 'Download Video',
 				showCancel: false,
 				showProgress: false,
+				paused: false,
 			});
 			outputLines = [];
+			currentJobId = null;
 		},
 	};
 }
@@ -2,6 +2,7 @@ import { type Writable, writable } from 'svelte/store';
 
 import type { SettingsState } from '$lib/types';
 
+import { getErrorMessage } from '$lib/utils/errors';
 import { getDownloadLocation, setDownloadLocation } from '$lib/utils/tauri';
 
 type SettingsStore = {
@@ -30,7 +31,7 @@ function createSettingsStore(): SettingsStore {
 					return {
 						...state,
 						loading: false,
-						error: error instanceof Error ? error.message : String(error),
+						error: getErrorMessage(error),
 					};
 				});
 			}
@@ -46,7 +47,7 @@ function createSettingsStore(): SettingsStore {
 					return {
 						...state,
 						loading: false,
-						error: error instanceof Error ? error.message : String(error),
+						error: getErrorMessage(error),
 					};
 				});
 				return false;
@@ -2,6 +2,7 @@ import { type Writable, writable } from 'svelte/store';
 
 import type { VideoFormat, VideoState } from '$lib/types';
 
+import { describeError } from '$lib/utils/errors';
 import { getVideoFormats, getVideoInfo } from '$lib/utils/tauri';
 import { isValidYouTubeUrl } from '$lib/utils/validation';
 
@@ -17,6 +18,7 @@ function createVideoStore(): VideoStore {
 	const { subscribe, set, update } = writable<VideoState>({
 		url: '',
 		title: '',
+		info: null,
 		formats: [],
 		selectedQuality: 'best',
 		loading: false,
@@ -51,6 +53,7 @@ function createVideoStore(): VideoStore {
 				set({
 					url: '',
 					title: '',
+					info: null,
 					formats: [],
 					selectedQuality: 'best',
 					loading: false,
@@ -67,6 +70,7 @@ function createVideoStore(): VideoStore {
 				set({
 					url: trimmedUrl,
 					title: '',
+					info: null,
 					formats: [],
 					selectedQuality: 'best',
 					loading: false,
@@ -108,6 +112,7 @@ function createVideoStore(): VideoStore {
 					set({
 						url: trimmedUrl,
 						title: info.title,
+						info,
 						formats: formats,
 						selectedQuality: 'best',
 						loading: false,
@@ -122,10 +127,11 @@ function createVideoStore(): VideoStore {
 				set({
 					url: trimmedUrl,
 					title: '',
+					info: null,
 					formats: [],
 					selectedQuality: 'best',
 					loading: false,
-					error: error instanceof Error ? error.message : String(error),
+					error: describeError(error),
 					isValid: false,
 					showDownloadSection: false,
 				});
@@ -139,6 +145,7 @@ function createVideoStore(): VideoStore {
 			set({
 				url: '',
 				title: '',
+				info: null,
 				formats: [],
 				selectedQuality: 'best',
 				loading: false,
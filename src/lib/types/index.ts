@@ -1,18 +1,281 @@
 // Tauri API Response Types
+export type ErrorKind =
+	| 'binary_missing'
+	| 'network'
+	| 'geo_restricted'
+	| 'unavailable'
+	| 'login_required'
+	| 'unsupported_url'
+	| 'format_unavailable'
+	| 'disk_full'
+	| 'cancelled'
+	| 'ffmpeg_missing'
+	| 'playlist'
+	| 'other';
+
+// Error object every command rejects with
+export interface AppError {
+	kind: ErrorKind;
+	message: string;
+}
+
 export interface YtdlpVersionInfo {
 	version: string;
 	source: 'path' | 'bundled';
 }
 
+export interface FfmpegVersionInfo {
+	version: string;
+	source: 'path' | 'bundled' | 'managed';
+	ffmpeg_path: string;
+	ffprobe_path: string | null;
+}
+
+export interface SubtitleTrack {
+	language: string;
+	name: string | null;
+	automatic: boolean;
+	formats: string[];
+}
+
+export interface Thumbnail {
+	url: string;
+	id: string | null;
+	width: number | null;
+	height: number | null;
+}
+
+export interface Chapter {
+	title: string | null;
+	start_time: number;
+	end_time: number;
+}
+
+export type LiveStatus = 'not_live' | 'is_live' | 'is_upcoming' | 'was_live' | 'post_live';
+
 export interface VideoInfo {
+	id: string | null;
+	extractor: string | null;
+	webpage_url: string | null;
 	title: string;
-	duration?: number;
-	view_count?: number;
+	description: string | null;
+	// YYYYMMDD
+	upload_date: string | null;
+	duration: number | null;
+	uploader: string | null;
+	channel: string | null;
+	channel_url: string | null;
+	view_count: number | null;
+	like_count: number | null;
+	tags: string[];
+	categories: string[];
+	age_limit: number | null;
+	live_status: LiveStatus | null;
+	availability: string | null;
+	thumbnails: Thumbnail[];
+	chapters: Chapter[];
+	subtitles: SubtitleTrack[];
 }
 
 export interface VideoFormat {
 	format_id: string;
+	resolution: string;
+	ext: string;
+	filesize: number | null;
 	quality_label: string;
+	vcodec: string;
+	acodec: string;
+	fps: number | null;
+	dynamic_range: string | null;
+	tbr: number | null;
+	container: string;
+	needs_merge: boolean;
+	quicktime_compatible: boolean;
+}
+
+export interface AudioFormat {
+	format_id: string;
+	ext: string;
+	acodec: string;
+	abr: number | null;
+	asr: number | null;
+	filesize: number | null;
+	language: string | null;
+	quality_label: string;
+}
+
+export type AudioCodec = 'mp3' | 'm4a' | 'opus' | 'flac' | 'wav';
+
+export interface AudioOptions {
+	codec: AudioCodec;
+	quality?: string | null;
+}
+
+export type Container = 'mp4' | 'mkv' | 'webm';
+
+export type SubtitleFormat = 'srt' | 'vtt' | 'ass';
+
+export interface SubtitleOptions {
+	languages: string[];
+	format: SubtitleFormat;
+	embed: boolean;
+	include_automatic?: boolean;
+}
+
+export interface DownloadOptions {
+	audio?: AudioOptions | null;
+	container?: Container | null;
+	subtitles?: SubtitleOptions | null;
+	embed_thumbnail?: boolean;
+	embed_metadata?: boolean;
+	embed_chapters?: boolean;
+	write_info_json?: boolean;
+	write_description?: boolean;
+	write_thumbnail?: boolean;
+	// Playlist entries to download, e.g. "1,3,5-7" or "1:" for all; only the video itself when unset
+	playlist_items?: string | null;
+	// Download from the cached probe result instead of extracting the URL again
+	reuse_probe?: boolean;
+	// Overrides the configured output template for this download
+	output_template?: string | null;
+}
+
+export interface PlaylistEntry {
+	index: number;
+	id: string | null;
+	title: string | null;
+	url: string | null;
+	duration: number | null;
+	availability: string | null;
+}
+
+export interface PlaylistPage {
+	playlist_id: string | null;
+	playlist_title: string | null;
+	total_count: number | null;
+	entries: PlaylistEntry[];
+	has_more: boolean;
+}
+
+export interface PlaylistProbeEntry {
+	probe_id: number;
+	playlist_id: string | null;
+	playlist_title: string | null;
+	total_count: number | null;
+	entry: PlaylistEntry;
+}
+
+export type ProbeState = 'finished' | 'failed' | 'cancelled';
+
+export interface PlaylistProbeStatus {
+	probe_id: number;
+	state: ProbeState;
+	entry_count: number;
+	error: AppError | null;
+}
+
+export type JobState = 'queued' | 'running' | 'paused' | 'finished' | 'failed' | 'cancelled';
+
+export interface DownloadJob {
+	id: number;
+	url: string;
+	quality: string | null;
+	options: DownloadOptions;
+	download_dir: string | null;
+	state: JobState;
+	message: string | null;
+	error: AppError | null;
+	result: DownloadResult | null;
+	attempts: JobAttempt[];
+	files: string[];
+}
+
+export interface JobAttempt {
+	attempt: number;
+	started_at: number;
+	finished_at: number;
+	error: AppError | null;
+	output: string[];
+}
+
+export interface CleanupPolicy {
+	delete_on_cancel: boolean;
+	delete_on_failure: boolean;
+}
+
+export interface LeftoverFile {
+	path: string;
+	size: number;
+	modified_at: number;
+}
+
+export interface OutputTemplatePreset {
+	id: string;
+	name: string;
+	template: string;
+}
+
+export interface OutputTemplatePreview {
+	filename: string;
+	// Valid templates can still be risky, e.g. without the video ID
+	warnings: string[];
+}
+
+export interface RetryPolicy {
+	max_attempts: number;
+	initial_delay_secs: number;
+	backoff_factor: number;
+	max_delay_secs: number;
+}
+
+export interface DownloadResult {
+	download_dir: string;
+	file_paths: string[];
+}
+
+export interface DownloadOutput {
+	job_id: number;
+	line: string;
+}
+
+export type ProgressPhase = 'downloading' | 'finished' | 'error' | 'postprocessing';
+
+export interface DownloadProgress {
+	job_id: number;
+	phase: ProgressPhase;
+	downloaded_bytes: number | null;
+	total_bytes: number | null;
+	total_bytes_estimated: boolean;
+	speed: number | null;
+	eta: number | null;
+	fragment_index: number | null;
+	fragment_count: number | null;
+	filename: string | null;
+	postprocessor: string | null;
+}
+
+export type HistoryOutcome = 'finished' | 'failed' | 'cancelled';
+
+export interface HistoryEntry {
+	id: number;
+	url: string;
+	extractor: string | null;
+	video_id: string | null;
+	title: string | null;
+	format: string | null;
+	file_path: string | null;
+	filesize: number | null;
+	started_at: number;
+	finished_at: number;
+	outcome: HistoryOutcome;
+	error: string | null;
+	error_kind: ErrorKind | null;
+}
+
+export interface HistoryFilter {
+	query?: string;
+	outcome?: HistoryOutcome;
+	extractor?: string;
 }
 
 // Store State Types
@@ -27,6 +290,7 @@ export interface AppState {
 export interface VideoState {
 	url: string;
 	title: string;
+	info: VideoInfo | null;
 	formats: VideoFormat[];
 	selectedQuality: string;
 	loading: boolean;
@@ -36,14 +300,18 @@ export interface VideoState {
 }
 
 export interface DownloadState {
+	jobId: number | null;
 	inProgress: boolean;
 	active: boolean;
 	outputLines: string[];
+	progress: DownloadProgress | null;
+	filePath: string | null;
 	status: string;
 	statusType: 'muted' | 'primary' | 'success' | 'error';
 	buttonText: string;
 	showCancel: boolean;
 	showProgress: boolean;
+	paused: boolean;
 }
 
 export interface SettingsState {
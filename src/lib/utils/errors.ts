@@ -0,0 +1,39 @@
+import type { AppError, ErrorKind } from '$lib/types';
+
+// Commands reject with an AppError object; anything else is an unexpected JS error
+export function isAppError(error: unknown): error is AppError {
+	return typeof error === 'object' && error !== null && 'kind' in error && 'message' in error;
+}
+
+export function getErrorMessage(error: unknown): string {
+	if (isAppError(error)) {
+		return error.message;
+	}
+	return error instanceof Error ? error.message : String(error);
+}
+
+// Suggested fix for each error kind
+const ERROR_HINTS: Partial<Record<ErrorKind, string>> = {
+	binary_missing: 'Install YT-DLP or use the bundled copy, then restart the app.',
+	network: 'Check your internet connection and try again.',
+	geo_restricted: 'This video is not available in your region.',
+	unavailable: 'The video is private or has been removed.',
+	login_required: 'This video requires signing in (age-restricted or members-only).',
+	unsupported_url: 'This site or link is not supported by YT-DLP.',
+	format_unavailable: 'The selected quality is not available. Try another quality or container.',
+	disk_full: 'Free up disk space or choose another download location.',
+	ffmpeg_missing: 'Install FFmpeg or place ffmpeg and ffprobe in the app\'s FFmpeg directory.',
+	playlist: 'This link is a playlist or channel. List its entries to choose videos to download.',
+};
+
+export function getErrorHint(error: unknown): string | null {
+	if (!isAppError(error)) {
+		return null;
+	}
+	return ERROR_HINTS[error.kind] ?? null;
+}
+
+// Short description for the UI: the suggested fix when there is one, otherwise the full message
+export function describeError(error: unknown): string {
+	return getErrorHint(error) ?? getErrorMessage(error);
+}
@@ -3,13 +3,41 @@ import { type Event, listen } from '@tauri-apps/api/event';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { open } from '@tauri-apps/plugin-dialog';
 
-import type { VideoFormat, VideoInfo, YtdlpVersionInfo } from '$lib/types';
+import type {
+	AudioFormat,
+	CleanupPolicy,
+	DownloadJob,
+	DownloadOptions,
+	DownloadOutput,
+	DownloadProgress,
+	FfmpegVersionInfo,
+	HistoryEntry,
+	HistoryFilter,
+	LeftoverFile,
+	OutputTemplatePreset,
+	OutputTemplatePreview,
+	PlaylistPage,
+	PlaylistProbeEntry,
+	PlaylistProbeStatus,
+	RetryPolicy,
+	VideoFormat,
+	VideoInfo,
+	YtdlpVersionInfo,
+} from '$lib/types';
 
 // Tauri API wrappers
 export async function getYtDlpVersion(): Promise<YtdlpVersionInfo> {
 	return await invoke<YtdlpVersionInfo>('get_ytdlp_version');
 }
 
+export async function getFfmpegVersion(): Promise<FfmpegVersionInfo> {
+	return await invoke<FfmpegVersionInfo>('get_ffmpeg_version');
+}
+
+export async function getFfmpegManagedDir(): Promise<string> {
+	return await invoke<string>('get_ffmpeg_managed_dir');
+}
+
 export async function getAppVersion(): Promise<string> {
 	return await invoke<string>('get_app_version');
 }
@@ -18,16 +46,124 @@ export async function getVideoInfo(url: string): Promise<VideoInfo> {
 	return await invoke<VideoInfo>('get_video_info', { url });
 }
 
-export async function getVideoFormats(url: string): Promise<VideoFormat[]> {
-	return await invoke<VideoFormat[]>('get_video_formats', { url });
+export async function getVideoFormats(url: string, allFormats = false): Promise<VideoFormat[]> {
+	return await invoke<VideoFormat[]>('get_video_formats', { url, allFormats });
+}
+
+export async function getAudioFormats(url: string): Promise<AudioFormat[]> {
+	return await invoke<AudioFormat[]>('get_audio_formats', { url });
+}
+
+export async function getRetryPolicy(): Promise<RetryPolicy> {
+	return await invoke<RetryPolicy>('get_retry_policy');
+}
+
+export async function setRetryPolicy(policy: RetryPolicy): Promise<void> {
+	return await invoke<void>('set_retry_policy', { policy });
 }
 
-export async function downloadVideo(url: string, quality: string): Promise<string> {
-	return await invoke<string>('download_video', { url, quality });
+export async function getCleanupPolicy(): Promise<CleanupPolicy> {
+	return await invoke<CleanupPolicy>('get_cleanup_policy');
 }
 
-export async function cancelDownload(): Promise<void> {
-	return await invoke<void>('cancel_download');
+export async function setCleanupPolicy(policy: CleanupPolicy): Promise<void> {
+	return await invoke<void>('set_cleanup_policy', { policy });
+}
+
+export async function scanLeftoverFiles(minAgeHours?: number): Promise<LeftoverFile[]> {
+	return await invoke<LeftoverFile[]>('scan_leftover_files', { minAgeHours });
+}
+
+export async function deleteLeftoverFiles(paths: string[]): Promise<void> {
+	return await invoke<void>('delete_leftover_files', { paths });
+}
+
+export async function getOutputTemplate(): Promise<string> {
+	return await invoke<string>('get_output_template');
+}
+
+export async function setOutputTemplate(template: string): Promise<void> {
+	return await invoke<void>('set_output_template', { template });
+}
+
+export async function listOutputTemplatePresets(): Promise<OutputTemplatePreset[]> {
+	return await invoke<OutputTemplatePreset[]>('list_output_template_presets');
+}
+
+export async function validateOutputTemplate(template: string): Promise<void> {
+	return await invoke<void>('validate_output_template', { template });
+}
+
+export async function previewOutputTemplate(url: string, template?: string): Promise<OutputTemplatePreview> {
+	return await invoke<OutputTemplatePreview>('preview_output_template', { url, template });
+}
+
+export async function clearProbeCache(): Promise<void> {
+	return await invoke<void>('clear_probe_cache');
+}
+
+export async function getPlaylistEntries(url: string, start?: number, count?: number): Promise<PlaylistPage> {
+	return await invoke<PlaylistPage>('get_playlist_entries', { url, start, count });
+}
+
+export async function startPlaylistProbe(url: string): Promise<number> {
+	return await invoke<number>('start_playlist_probe', { url });
+}
+
+export async function cancelPlaylistProbe(probeId: number): Promise<void> {
+	return await invoke<void>('cancel_playlist_probe', { probeId });
+}
+
+export async function downloadVideo(url: string, quality: string, options?: DownloadOptions): Promise<number> {
+	return await invoke<number>('download_video', { url, quality, options });
+}
+
+export async function cancelDownload(jobId: number): Promise<void> {
+	return await invoke<void>('cancel_download', { jobId });
+}
+
+export async function pauseDownload(jobId: number): Promise<void> {
+	return await invoke<void>('pause_download', { jobId });
+}
+
+export async function resumeDownload(jobId: number): Promise<void> {
+	return await invoke<void>('resume_download', { jobId });
+}
+
+export async function listInterruptedDownloads(): Promise<DownloadJob[]> {
+	return await invoke<DownloadJob[]>('list_interrupted_downloads');
+}
+
+export async function resumeInterruptedDownloads(ids: number[]): Promise<number[]> {
+	return await invoke<number[]>('resume_interrupted_downloads', { ids });
+}
+
+export async function discardInterruptedDownloads(ids: number[]): Promise<void> {
+	return await invoke<void>('discard_interrupted_downloads', { ids });
+}
+
+export async function listDownloads(): Promise<DownloadJob[]> {
+	return await invoke<DownloadJob[]>('list_downloads');
+}
+
+export async function openDownloadedFile(path: string): Promise<void> {
+	return await invoke<void>('open_downloaded_file', { path });
+}
+
+export async function revealDownloadedFile(path: string): Promise<void> {
+	return await invoke<void>('reveal_downloaded_file', { path });
+}
+
+export async function listHistory(filter?: HistoryFilter): Promise<HistoryEntry[]> {
+	return await invoke<HistoryEntry[]>('list_history', { filter });
+}
+
+export async function deleteHistoryEntries(ids: number[]): Promise<void> {
+	return await invoke<void>('delete_history_entries', { ids });
+}
+
+export async function clearHistory(): Promise<void> {
+	return await invoke<void>('clear_history');
 }
 
 export async function getDownloadLocation(): Promise<string> {
@@ -38,6 +174,14 @@ export async function setDownloadLocation(path: string): Promise<void> {
 	return await invoke<void>('set_download_location', { path });
 }
 
+export async function getMaxConcurrentDownloads(): Promise<number> {
+	return await invoke<number>('get_max_concurrent_downloads');
+}
+
+export async function setMaxConcurrentDownloads(limit: number): Promise<void> {
+	return await invoke<void>('set_max_concurrent_downloads', { limit });
+}
+
 export async function openFolderDialog(defaultPath?: string): Promise<string | null> {
 	const selected = await open({
 		directory: true,
@@ -55,20 +199,44 @@ export async function openFolderDialog(defaultPath?: string): Promise<string | n
 export type ProgressUnlisten = () => Promise<void>;
 
 export async function setupProgressListener(
-	onProgress: (line: string) => void,
-	onError: (error: string) => void,
+	onOutput: (output: DownloadOutput) => void,
+	onProgress: (progress: DownloadProgress) => void,
+	onJobUpdate: (job: DownloadJob) => void,
 ): Promise<ProgressUnlisten> {
-	const unlistenProgress = await listen<string>('download-output', (event: Event<string>) => {
+	const unlistenOutput = await listen<DownloadOutput>('download-output', (event: Event<DownloadOutput>) => {
+		onOutput(event.payload);
+	});
+
+	const unlistenProgress = await listen<DownloadProgress>('download-progress', (event: Event<DownloadProgress>) => {
 		onProgress(event.payload);
 	});
 
-	const unlistenError = await listen<string>('download-error', (event: Event<string>) => {
-		onError(event.payload);
+	const unlistenJob = await listen<DownloadJob>('download-job', (event: Event<DownloadJob>) => {
+		onJobUpdate(event.payload);
 	});
 
 	return async () => {
+		await unlistenOutput();
 		await unlistenProgress();
-		await unlistenError();
+		await unlistenJob();
+	};
+}
+
+export async function setupPlaylistProbeListener(
+	onEntry: (entry: PlaylistProbeEntry) => void,
+	onStatus: (status: PlaylistProbeStatus) => void,
+): Promise<ProgressUnlisten> {
+	const unlistenEntry = await listen<PlaylistProbeEntry>('playlist-entry', (event: Event<PlaylistProbeEntry>) => {
+		onEntry(event.payload);
+	});
+
+	const unlistenStatus = await listen<PlaylistProbeStatus>('playlist-probe', (event: Event<PlaylistProbeStatus>) => {
+		onStatus(event.payload);
+	});
+
+	return async () => {
+		await unlistenEntry();
+		await unlistenStatus();
 	};
 }
 